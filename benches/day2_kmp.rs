@@ -0,0 +1,22 @@
+//! Compares the string-doubling and KMP predicates of day 2, puzzle 2 on a large range of numbers.
+//! Run with `cargo bench --bench day2_kmp`.
+
+use std::{hint::black_box, time::{Duration, Instant}};
+
+use aoc2025::solutions::day2;
+
+const RANGE: std::ops::Range<u64> = 1_000_000_000..1_005_000_000;
+
+fn time(name: &str, predicate: impl Fn(u64) -> bool) -> Duration {
+    let start = Instant::now();
+    let matches = RANGE.filter(|&i| predicate(black_box(i))).count();
+    let elapsed = start.elapsed();
+    println!("{:<16} {:>12?} (matches: {})", name, elapsed, matches);
+    elapsed
+}
+
+fn main() {
+    let doubling = time("string doubling", day2::is_repeated_pattern);
+    let kmp = time("kmp", day2::has_repeating_pattern_kmp);
+    println!("speedup: {:.1}x", doubling.as_secs_f64() / kmp.as_secs_f64());
+}
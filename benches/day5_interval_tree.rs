@@ -0,0 +1,58 @@
+//! Compares the interval tree and the merged binary search membership checks of day 5, puzzle 1 for a growing
+//! number of generated intervals. Run with `cargo bench --bench day5_interval_tree`.
+
+use std::{hint::black_box, ops::RangeInclusive, time::{Duration, Instant}};
+
+use aoc2025::solutions::day5::{self, IntervalNode};
+
+const INTERVAL_COUNTS: [usize; 6] = [10, 100, 1_000, 10_000, 100_000, 1_000_000];
+const QUERY_COUNT: usize = 100_000;
+const UNIVERSE: i64 = 1_000_000_000_000;
+
+// A small xorshift generator, so the inputs are the same on every run without pulling in a dependency
+fn generator(mut state: u64) -> impl FnMut() -> i64 {
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % UNIVERSE as u64) as i64
+    }
+}
+
+fn intervals(count: usize) -> Vec<RangeInclusive<i64>> {
+    let mut next = generator(0x5eed);
+    // Intervals are kept short relative to the universe, so that lookups hit and miss alike
+    let max_width = UNIVERSE / count as i64 / 2;
+    (0..count)
+        .map(|_| {
+            let start = next();
+            start..=start + next() % max_width
+        })
+        .collect()
+}
+
+fn time(name: &str, f: impl FnOnce() -> usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let result = black_box(f());
+    let elapsed = start.elapsed();
+    println!("  {:<24} {:>12?} (contained: {})", name, elapsed, result);
+    (elapsed, result)
+}
+
+fn main() {
+    let mut next = generator(0xc0ffee);
+    let integers: Vec<i64> = (0..QUERY_COUNT).map(|_| next()).collect();
+    for count in INTERVAL_COUNTS {
+        println!("{} intervals, {} queries", count, QUERY_COUNT);
+        let intervals = intervals(count);
+        let (tree_time, tree_count) = time("interval tree", || {
+            let tree = IntervalNode::from(intervals.clone());
+            day5::count_contained(&tree, &integers)
+        });
+        let (linear_time, linear_count) = time("merged binary search", || {
+            day5::count_contained_linear(&intervals, &integers)
+        });
+        assert_eq!(tree_count, linear_count, "both approaches must count the same integers");
+        println!("  ratio (tree / merged): {:.2}", tree_time.as_secs_f64() / linear_time.as_secs_f64());
+    }
+}
@@ -0,0 +1,41 @@
+//! Compares the `read_at` and memory-mapped input paths of day 6 on an extremely wide generated input.
+//! Run with `cargo bench --features mmap --bench day6_mmap`.
+
+use std::time::{Duration, Instant};
+
+use aoc2025::{solutions::day6, utils};
+
+const PROBLEM_COUNT: usize = 50_000;
+
+// Builds an input with PROBLEM_COUNT columns of 3 numbers each, alternating between addition and multiplication
+fn wide_input() -> String {
+    let mut rows = vec![String::new(); 4];
+    for p in 0..PROBLEM_COUNT {
+        rows[0].push_str(&format!("{:>3} ", p % 1000));
+        rows[1].push_str(&format!("{:>3} ", (p * 7) % 100));
+        rows[2].push_str(&format!("{:>3} ", (p * 13) % 10));
+        rows[3].push_str(if p % 2 == 0 { "+   " } else { "*   " });
+    }
+    rows.join("\n") + "\n"
+}
+
+fn time(name: &str, f: impl Fn() -> i64) -> Duration {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    println!("{:<16} {:>12?} (result: {})", name, elapsed, result);
+    elapsed
+}
+
+fn main() {
+    let path = utils::string_to_file(&wide_input());
+    let read_at_1 = time("puzzle1", || day6::puzzle1(&path));
+    let mmap_1 = time("puzzle1_mmap", || day6::puzzle1_mmap(&path));
+    let read_at_2 = time("puzzle2", || day6::puzzle2(&path));
+    let mmap_2 = time("puzzle2_mmap", || day6::puzzle2_mmap(&path));
+    println!(
+        "speedup: puzzle1 {:.1}x, puzzle2 {:.1}x",
+        read_at_1.as_secs_f64() / mmap_1.as_secs_f64(),
+        read_at_2.as_secs_f64() / mmap_2.as_secs_f64()
+    );
+}
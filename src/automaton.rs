@@ -0,0 +1,89 @@
+//! # Cellular automata
+//!
+//! Some puzzles evolve a grid of characters in generations, where the next state of every cell only depends on
+//! its current state and the states of its 8 adjacent cells (e.g. the removal of paper rolls in day 4). The
+//! [`Automaton`] trait describes such a rule, and [`step_grid`] applies it to every cell of a grid at once.
+
+/// Offsets of the 8 adjacent cells (row_offset, col_offset), right and down are positive
+pub const NEIGHBOR_OFFSETS: [(isize, isize); 8] = [
+    (0, 1),
+    (0, -1),
+    (1, 0),
+    (-1, 0),
+    (-1, 1),
+    (1, 1),
+    (-1, -1),
+    (1, -1),
+];
+
+/// A rule computing the next state of a cell from its current state and the states of its neighbors.
+pub trait Automaton {
+    /// Returns the next state of `cell`. The neighbors only include the adjacent cells within the grid,
+    /// so cells on the edges have fewer than 8 neighbors.
+    fn next_state(&self, cell: char, neighbors: &[char]) -> char;
+}
+
+/// Computes the next generation of the grid by applying the rule to every cell simultaneously,
+/// i.e. the next state of a cell never depends on the next state of another cell.
+#[must_use]
+pub fn step_grid(automaton: &impl Automaton, grid: &[Vec<char>]) -> Vec<Vec<char>> {
+    let mut neighbors = Vec::with_capacity(NEIGHBOR_OFFSETS.len());
+    grid.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(j, &cell)| {
+                    neighbors.clear();
+                    neighbors.extend(NEIGHBOR_OFFSETS.iter().filter_map(|(di, dj)| {
+                        let ni = i.checked_add_signed(*di)?;
+                        let nj = j.checked_add_signed(*dj)?;
+                        grid.get(ni)?.get(nj).copied()
+                    }));
+                    automaton.next_state(cell, &neighbors)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Conway's Game of Life, where # is a live cell
+    struct GameOfLife;
+
+    impl Automaton for GameOfLife {
+        fn next_state(&self, cell: char, neighbors: &[char]) -> char {
+            match (cell, neighbors.iter().filter(|&&c| c == '#').count()) {
+                ('#', 2..=3) | (_, 3) => '#',
+                _ => '.',
+            }
+        }
+    }
+
+    fn grid(s: &str) -> Vec<Vec<char>> {
+        s.lines().map(|l| l.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_blinker() {
+        let vertical = grid(".....\n..#..\n..#..\n..#..\n.....");
+        let horizontal = grid(".....\n.....\n.###.\n.....\n.....");
+        assert_eq!(step_grid(&GameOfLife, &vertical), horizontal);
+        assert_eq!(step_grid(&GameOfLife, &horizontal), vertical);
+    }
+
+    #[test]
+    fn test_edge_neighbors() {
+        // Replaces every cell with its neighbor count, which is 3 in the corners and 5 on the edges
+        struct NeighborCount;
+        impl Automaton for NeighborCount {
+            fn next_state(&self, _cell: char, neighbors: &[char]) -> char {
+                char::from_digit(neighbors.len() as u32, 10).unwrap()
+            }
+        }
+        assert_eq!(step_grid(&NeighborCount, &grid("...\n...\n...")), grid("353\n585\n353"));
+    }
+}
@@ -1,9 +1,9 @@
+use std::fs;
+
 use aoc2025::solutions::day2;
-use aoc2025::utils;
 
 fn main() {
-    let lines = utils::lines_from_file("inputs/day2.txt");
-    let input = lines.first().expect("input file for day 2 must not be empty");
+    let input: &String = &fs::read_to_string("inputs/day2.txt").expect("failed to read day 2 input");
     println!("Puzzle 1: {}", day2::puzzle1(input));
     println!("Puzzle 2: {}", day2::puzzle2(input));
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ use aoc2025::solutions::day6;
 const DAY_6_FILE: &str = "inputs/day6.txt";
 
 fn main() {
-    println!("Puzzle 1:\n{}", day6::puzzle1(DAY_6_FILE));
-    println!("Puzzle 2:\n{}", day6::puzzle2(DAY_6_FILE));
+    let (puzzle1, puzzle2) = day6::solve_both(DAY_6_FILE);
+    println!("Puzzle 1:\n{}", puzzle1);
+    println!("Puzzle 2:\n{}", puzzle2);
 }
\ No newline at end of file
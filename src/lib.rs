@@ -1,2 +1,3 @@
+pub mod automaton;
 pub mod solutions;
 pub mod utils;
\ No newline at end of file
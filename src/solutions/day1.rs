@@ -2,6 +2,8 @@
 
 //! # Day 1: Secret Entrance ([challenge description](https://adventofcode.com/2025/day/1))
 
+use std::io::BufRead;
+
 /// # Puzzle 1
 /// 
 /// ## Summary
@@ -19,34 +21,97 @@
 /// ## Optimizations
 /// Apart from batching and parallel processing, I can't think of a quicker way to do this, especially since
 /// the result of each consecutive arithmetic operation needs to checked.
+///
+/// Malformed rotations and an initial position beyond 99 are returned as a `Day1Error` rather than panicking,
+/// so that partially corrupt input can be reported.
 /// 
-pub fn puzzle1(init_pos: u8, rotations: Vec<String>) -> u64 {
-    if init_pos > 99 {
-        panic!("the initial position must be within 0 to 99");
-    }
-    let mut curr_pos: i64 = init_pos as i64;
+pub fn puzzle1(init_pos: u8, rotations: Vec<String>) -> Result<u64, Day1Error> {
+    puzzle1_with_size(init_pos as u64, DIAL_SIZE, &rotations)
+}
+
+// The number of positions of the dial of the puzzles, 0 through 99
+const DIAL_SIZE: u64 = 100;
+
+/// Same as puzzle 1, but for a dial with `size` positions (0 through size - 1) instead of 100, e.g. 360 degrees.
+pub fn puzzle1_with_size(init_pos: u64, size: u64, rotations: &[impl AsRef<str>]) -> Result<u64, Day1Error> {
+    check_init_pos(init_pos, size)?;
+    Ok(count_stops(init_pos, size, &try_parse_rotations(rotations)?))
+}
+
+/// Same as puzzle 1, but borrows the rotations, so both puzzles can be solved without cloning the input.
+pub fn puzzle1_slice(init_pos: u8, rotations: &[impl AsRef<str>]) -> u64 {
+    puzzle1_oriented(init_pos, rotations, false)
+}
+
+/// Same as puzzle 1, but streams the rotations one line at a time from a reader rather than loading them all
+/// into memory first, e.g. for a very large rotation list. Blank lines are skipped, and like puzzle 1, malformed
+/// rotations are returned as a `Day1Error`, as are lines that can't be read.
+pub fn puzzle1_reader<R: BufRead>(init_pos: u8, mut reader: R) -> Result<u64, Day1Error> {
+    check_init_pos(init_pos as u64, DIAL_SIZE)?;
+    let mut dial = Dial::new(init_pos as u64, DIAL_SIZE);
     let mut zero_count: u64 = 0;
-    for rotation in rotations.iter() {
-        let mut chars: std::str::Chars<'_> = rotation.chars();
-        let multiplier: i64 = match chars.next() {
-            Some('L') => {
-                -1
-            }
-            Some('R') => {
-                1
-            }
-            _ => panic!("rotations must start with L or R"),
-        };
-        let dist: i64 = chars
-            .as_str()
-            .parse::<u64>()
-            .expect("Failed to parse distance as an integer") as i64;
-        curr_pos = ((((curr_pos) + (multiplier * dist)) % 100) + 100) % 100;
-        if curr_pos == 0 {
+    let mut line = String::new();
+    for index in 0.. {
+        line.clear();
+        let bytes_read = reader
+            .read_line(&mut line)
+            .map_err(|e| Day1Error::ReadFailed { index, message: e.to_string() })?;
+        if bytes_read == 0 {
+            break;
+        }
+        let rotation = line.trim();
+        if rotation.is_empty() {
+            continue;
+        }
+        if dial.turn(parse_rotation(index, rotation)?).position == 0 {
             zero_count += 1;
         }
     }
-    zero_count
+    Ok(zero_count)
+}
+
+/// Same as puzzle 1, but for a mirrored lock mechanism when `reversed` is set, where L turns the dial
+/// clockwise and R turns it counter-clockwise.
+pub fn puzzle1_oriented(init_pos: u8, rotations: &[impl AsRef<str>], reversed: bool) -> u64 {
+    count_stops_parsed(init_pos, &parse_rotations(rotations, reversed))
+}
+
+/// Same as puzzle 1, but for rotations that have already been parsed, e.g. to evaluate the same rotations
+/// from many starting positions without parsing them every time.
+pub fn count_stops_parsed(init_pos: u8, rotations: &[Rotation]) -> u64 {
+    count_stops(init_pos as u64, DIAL_SIZE, rotations)
+}
+
+fn count_stops(init_pos: u64, size: u64, rotations: &[Rotation]) -> u64 {
+    let mut dial = Dial::new(init_pos, size);
+    rotations.iter().filter(|&&rotation| dial.turn(rotation).position == 0).count() as u64
+}
+
+/// Returns the position the dial stops at after each rotation, in order, e.g. to check the intermediate
+/// states of the dial rather than only the number of stops on 0.
+pub fn positions(init_pos: u8, rotations: &[impl AsRef<str>]) -> Vec<u8> {
+    let mut dial = Dial::new(init_pos as u64, DIAL_SIZE);
+    rotations
+        .iter()
+        .map(|rotation| dial.turn(Rotation::parse(rotation.as_ref())).position as u8)
+        .collect()
+}
+
+/// A harder variant of puzzle 1, where the lock only opens after the dial stops on the number 0 several times
+/// in a row. Returns the longest run of consecutive rotations that ended on 0.
+pub fn max_consecutive_zero_stops(init_pos: u8, rotations: &[impl AsRef<str>]) -> u64 {
+    let mut dial = Dial::new(init_pos as u64, DIAL_SIZE);
+    let mut current_run: u64 = 0;
+    let mut max_run: u64 = 0;
+    for rotation in rotations {
+        if dial.turn(Rotation::parse(rotation.as_ref())).position == 0 {
+            current_run += 1;
+            max_run = max_run.max(current_run);
+        } else {
+            current_run = 0;
+        }
+    }
+    max_run
 }
 
 /// # Puzzle 2
@@ -65,37 +130,316 @@ pub fn puzzle1(init_pos: u8, rotations: Vec<String>) -> u64 {
 /// rotations, a distance equal or greater than the sum of the current position and the remainder
 /// points at 0 once.
 ///
-pub fn puzzle2(init_pos: u8, rotations: Vec<String>) -> u64 {
-    if init_pos > 99 {
-        panic!("the initial position must be within 0 to 99");
-    }
-    let mut curr_pos: i64 = init_pos as i64;
-    let mut zero_count: u64 = 0;
-    for rotation in rotations.iter() {
-        let mut chars: std::str::Chars<'_> = rotation.chars();
-        let multiplier: i64 = match chars.next() {
-            Some('L') => {
-                -1
+/// Like puzzle 1, malformed input is returned as a `Day1Error`.
+///
+pub fn puzzle2(init_pos: u8, rotations: Vec<String>) -> Result<u64, Day1Error> {
+    puzzle2_with_size(init_pos as u64, DIAL_SIZE, &rotations)
+}
+
+/// Same as puzzle 2, but for a dial with `size` positions like `puzzle1_with_size`, so a full turn is a
+/// distance of `size` rather than 100.
+pub fn puzzle2_with_size(init_pos: u64, size: u64, rotations: &[impl AsRef<str>]) -> Result<u64, Day1Error> {
+    check_init_pos(init_pos, size)?;
+    Ok(crossings(init_pos, size, &try_parse_rotations(rotations)?).sum())
+}
+
+/// Same as puzzle 2, but borrows the rotations, so both puzzles can be solved without cloning the input.
+pub fn puzzle2_slice(init_pos: u8, rotations: &[impl AsRef<str>]) -> u64 {
+    puzzle2_oriented(init_pos, rotations, false)
+}
+
+/// Same as puzzle 2, but for a mirrored lock mechanism when `reversed` is set, where L turns the dial
+/// clockwise and R turns it counter-clockwise.
+pub fn puzzle2_oriented(init_pos: u8, rotations: &[impl AsRef<str>], reversed: bool) -> u64 {
+    count_crossings_parsed(init_pos, &parse_rotations(rotations, reversed))
+}
+
+/// Same as puzzle 2, but for rotations that have already been parsed, like `count_stops_parsed`.
+pub fn count_crossings_parsed(init_pos: u8, rotations: &[Rotation]) -> u64 {
+    crossings(init_pos as u64, DIAL_SIZE, rotations).sum()
+}
+
+/// Same as puzzle 2, but rather than the total, returns how many times each rotation on its own passes or stops on
+/// the number 0. A rotation counts one crossing per full turn (dist/100), plus one if the rest of its distance
+/// reaches 0. The counts add up to the answer to puzzle 2.
+pub fn crossings_per_rotation(init_pos: u8, rotations: &[impl AsRef<str>]) -> Vec<u64> {
+    crossings(init_pos as u64, DIAL_SIZE, &parse_rotations(rotations, false)).collect()
+}
+
+/// Same as puzzle 2, but splits the crossings of the number 0 by the direction of the rotation that made them,
+/// as `(left_crossings, right_crossings)`. Full turns count towards the direction of their own rotation.
+pub fn crossings_by_direction(init_pos: u8, rotations: &[impl AsRef<str>]) -> (u64, u64) {
+    let rotations = parse_rotations(rotations, false);
+    crossings(init_pos as u64, DIAL_SIZE, &rotations)
+        .zip(rotations.iter())
+        .fold((0, 0), |(left, right), (count, rotation)| {
+            if rotation.multiplier == -1 {
+                (left + count, right)
+            } else {
+                (left, right + count)
             }
-            Some('R') => {
-                1
+        })
+}
+
+// Lazily yields the number of times each rotation passes or stops on the number 0 of a dial with size positions
+fn crossings(init_pos: u64, size: u64, rotations: &[Rotation]) -> impl Iterator<Item = u64> + '_ {
+    let mut dial = Dial::new(init_pos, size);
+    rotations.iter().map(move |&rotation| dial.turn(rotation).crossings)
+}
+
+// A dial with size positions, which every variant of the puzzles turns one rotation at a time
+struct Dial {
+    position: i64,
+    size: i64,
+}
+
+// Where the dial stopped after a rotation, and how many times it passed or stopped on the number 0 on the way
+struct Step {
+    position: u64,
+    crossings: u64,
+}
+
+impl Dial {
+    fn new(init_pos: u64, size: u64) -> Self {
+        if init_pos >= size {
+            panic!("the initial position must be within 0 to {}", size.saturating_sub(1));
+        }
+        Dial { position: init_pos as i64, size: size as i64 }
+    }
+
+    // Counts the crossings like described in puzzle 2, which includes stopping on 0 like in puzzle 1
+    fn turn(&mut self, Rotation { multiplier, distance: dist }: Rotation) -> Step {
+        let quot: i64 = dist / self.size;
+        let rem: i64 = dist % self.size;
+        let mut zero_count = quot as u64;
+        if self.position != 0
+            && (multiplier == -1 && rem >= self.position || multiplier == 1 && self.position + rem >= self.size) {
+                zero_count += 1;
             }
-            _ => panic!("rotations must start with L or R"),
-        };
-        let dist: i64 = chars
-            .as_str()
+        self.position = (self.position + multiplier * rem).rem_euclid(self.size);
+        Step { position: self.position as u64, crossings: zero_count }
+    }
+}
+
+/// Parses every rotation once, swapping their directions if the lock is mirrored
+pub fn parse_rotations(rotations: &[impl AsRef<str>], reversed: bool) -> Vec<Rotation> {
+    rotations
+        .iter()
+        .map(|r| Rotation::parse(r.as_ref()).oriented(reversed))
+        .collect()
+}
+
+/// A parsed rotation like L68 or R48
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    /// -1 for left (counter-clockwise) and 1 for right (clockwise) rotations
+    multiplier: i64,
+    distance: i64,
+}
+
+impl Rotation {
+    /// Parses a rotation, where the direction is either L/R or spelled out as CCW/CW, e.g. CCW30 is the same as L30.
+    /// Panics if the rotation is malformed.
+    pub fn parse(rotation: &str) -> Self {
+        rotation.parse().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    // Swaps the direction of the rotation if the lock is mirrored
+    fn oriented(self, reversed: bool) -> Self {
+        if reversed {
+            Rotation { multiplier: -self.multiplier, ..self }
+        } else {
+            self
+        }
+    }
+}
+
+impl std::str::FromStr for Rotation {
+    type Err = String;
+
+    fn from_str(rotation: &str) -> Result<Self, Self::Err> {
+        let (multiplier, dist_str) = split_direction(rotation)
+            .ok_or("rotations must start with L, R, CCW or CW".to_string())?;
+        let distance = dist_str
             .parse::<u64>()
-            .expect("Failed to parse distance as an integer") as i64;
-        let quot: i64 = dist / 100;
-        let rem: i64 = dist % 100;
-        zero_count += quot as u64;
-        if curr_pos != 0
-            && (multiplier == -1 && rem >= curr_pos || multiplier == 1 && curr_pos + rem >= 100) {
-                zero_count += 1;
+            .map_err(|e| format!("Failed to parse distance as an integer: {}", e))? as i64;
+        Ok(Rotation { multiplier, distance })
+    }
+}
+
+// Splits a rotation into the multiplier of its direction and the rest of the rotation, which should be the distance
+fn split_direction(rotation: &str) -> Option<(i64, &str)> {
+    if let Some(rest) = rotation.strip_prefix("CCW") {
+        Some((-1, rest))
+    } else if let Some(rest) = rotation.strip_prefix("CW") {
+        Some((1, rest))
+    } else if let Some(rest) = rotation.strip_prefix('L') {
+        Some((-1, rest))
+    } else if let Some(rest) = rotation.strip_prefix('R') {
+        Some((1, rest))
+    } else {
+        None
+    }
+}
+
+/// The ways the input of the puzzles may be malformed. The rotation errors carry the index of the rotation
+/// (counted from 0) together with the rotation itself, so the offending line can be reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Day1Error {
+    /// The rotation doesn't start with L, R, CCW or CW
+    InvalidDirection { index: usize, rotation: String },
+    /// The distance of the rotation isn't a non-negative integer
+    InvalidDistance { index: usize, rotation: String },
+    /// The initial position is beyond the numbers of a dial with the given number of positions
+    InitOutOfRange { init_pos: u64, size: u64 },
+    /// A line of a streamed input couldn't be read, e.g. because it isn't valid UTF-8
+    ReadFailed { index: usize, message: String },
+}
+
+impl std::fmt::Display for Day1Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Day1Error::InvalidDirection { index, rotation } => {
+                write!(f, "line {}: bad rotation '{}' (must start with L, R, CCW or CW)", index + 1, rotation)
             }
-        curr_pos = (curr_pos + multiplier * rem).rem_euclid(100);
+            Day1Error::InvalidDistance { index, rotation } => {
+                write!(f, "line {}: bad rotation '{}' (distance must be an integer)", index + 1, rotation)
+            }
+            Day1Error::InitOutOfRange { init_pos, size } => {
+                write!(f, "the initial position {} must be below the dial size {}", init_pos, size)
+            }
+            Day1Error::ReadFailed { index, message } => {
+                write!(f, "line {}: failed to read rotation ({})", index + 1, message)
+            }
+        }
     }
-    zero_count
+}
+
+impl std::error::Error for Day1Error {}
+
+fn check_init_pos(init_pos: u64, size: u64) -> Result<(), Day1Error> {
+    if init_pos >= size {
+        return Err(Day1Error::InitOutOfRange { init_pos, size });
+    }
+    Ok(())
+}
+
+// Same as parse_rotations, but the first malformed rotation is returned as an error instead of panicking
+fn try_parse_rotations(rotations: &[impl AsRef<str>]) -> Result<Vec<Rotation>, Day1Error> {
+    rotations
+        .iter()
+        .enumerate()
+        .map(|(index, r)| parse_rotation(index, r.as_ref()))
+        .collect()
+}
+
+// Parses the rotation at the given index, returning a malformed rotation as an error
+fn parse_rotation(index: usize, rotation: &str) -> Result<Rotation, Day1Error> {
+    let (multiplier, dist_str) = split_direction(rotation)
+        .ok_or_else(|| Day1Error::InvalidDirection { index, rotation: rotation.to_string() })?;
+    let distance = dist_str
+        .parse::<u64>()
+        .map_err(|_| Day1Error::InvalidDistance { index, rotation: rotation.to_string() })? as i64;
+    Ok(Rotation { multiplier, distance })
+}
+
+/// Checks every rotation up front, so a batch can be rejected before any of it is processed. Returns the index
+/// and the parse error of every malformed rotation, rather than stopping at the first one.
+pub fn validate_rotations(rotations: &[impl AsRef<str>]) -> Result<(), Vec<(usize, String)>> {
+    let errors: Vec<(usize, String)> = rotations
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| r.as_ref().parse::<Rotation>().err().map(|e| (i, e)))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// # Both puzzles
+///
+/// Solves puzzle 1 and puzzle 2 in a single loop, parsing each rotation once and updating both the counter of
+/// rotations ending on 0 and the counter of times 0 is pointed at. Returns `(puzzle1, puzzle2)`.
+///
+pub fn solve_both(init_pos: u8, rotations: &[impl AsRef<str>]) -> (u64, u64) {
+    let report = analyze(init_pos, rotations);
+    (report.stops_on_zero, report.crossings_of_zero)
+}
+
+/// A summary of a series of rotations applied to the lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockReport {
+    /// The number of rotations ending on 0 (the answer to puzzle 1)
+    pub stops_on_zero: u64,
+    /// The number of times 0 is pointed at, during or at the end of a rotation (the answer to puzzle 2)
+    pub crossings_of_zero: u64,
+    /// The dial position after the last rotation
+    pub final_position: u8,
+    /// The sum of all rotation distances, regardless of direction
+    pub total_distance: u64,
+}
+
+impl std::fmt::Display for LockReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Puzzle 1: {}", self.stops_on_zero)?;
+        writeln!(f, "Puzzle 2: {}", self.crossings_of_zero)?;
+        writeln!(f, "Final position: {}", self.final_position)?;
+        write!(f, "Total distance: {}", self.total_distance)
+    }
+}
+
+/// # Lock report
+///
+/// Same as `solve_both`, but also reports the final dial position and the total distance rotated,
+/// all computed in a single loop over the rotations.
+///
+pub fn analyze(init_pos: u8, rotations: &[impl AsRef<str>]) -> LockReport {
+    let mut dial = Dial::new(init_pos as u64, DIAL_SIZE);
+    let mut report = LockReport {
+        stops_on_zero: 0,
+        crossings_of_zero: 0,
+        final_position: init_pos,
+        total_distance: 0,
+    };
+    for rotation in rotations.iter().map(|r| Rotation::parse(r.as_ref())) {
+        let step = dial.turn(rotation);
+        report.total_distance += rotation.distance as u64;
+        report.crossings_of_zero += step.crossings;
+        if step.position == 0 {
+            report.stops_on_zero += 1;
+        }
+        report.final_position = step.position as u8;
+    }
+    report
+}
+
+/// # Best starting position
+///
+/// Finds the initial dial position (0 to 99) that makes the rotations of puzzle 1 stop on the number 0
+/// the most times, returning the position together with its stop count. The lowest position wins a tie.
+///
+/// Rather than running puzzle 1 for all 100 starting positions, note that the dial position after k rotations
+/// is the starting position plus the sum of the first k signed distances (mod 100). A rotation therefore stops
+/// on 0 exactly when the starting position is the negated prefix sum (mod 100), so counting the prefix sums by
+/// their remainder gives the stop count of every starting position in a single O(n) pass.
+///
+pub fn best_start(rotations: &[impl AsRef<str>]) -> (u8, u64) {
+    let mut stops = [0u64; 100];
+    let mut offset: i64 = 0;
+    for rotation in rotations {
+        let rot = Rotation::parse(rotation.as_ref());
+        offset = (offset + rot.multiplier * rot.distance).rem_euclid(100);
+        stops[((100 - offset) % 100) as usize] += 1;
+    }
+    let mut best = (0u8, stops[0]);
+    for (start, &count) in stops.iter().enumerate() {
+        if count > best.1 {
+            best = (start as u8, count);
+        }
+    }
+    best
 }
 
 #[cfg(test)]
@@ -112,7 +456,7 @@ mod tests {
                     "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
                 ])
             ),
-            3
+            Ok(3)
         );
     }
 
@@ -125,7 +469,7 @@ mod tests {
                     "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
                 ])
             ),
-            6
+            Ok(6)
         );
     }
 
@@ -139,7 +483,181 @@ mod tests {
                     "R1000", "L1000", "R1", "R1000", "L234", "R32", "R1000", "R99", "R202"
                 ])
             ),
-            54
+            Ok(54)
+        );
+    }
+
+    #[test]
+    fn test_slice() {
+        let rotations = [
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+        ];
+        assert_eq!(puzzle1_slice(50, &rotations), 3);
+        assert_eq!(puzzle2_slice(50, &rotations), 6);
+        let owned = utils::str_slice_to_vec_string(&rotations);
+        assert_eq!(puzzle1_slice(50, &owned), 3);
+        assert_eq!(puzzle2_slice(50, &owned), 6);
+    }
+
+    #[test]
+    fn test_clockwise_tokens() {
+        assert_eq!(Rotation::parse("CW48"), Rotation::parse("R48"));
+        assert_eq!(Rotation::parse("CCW30"), Rotation::parse("L30"));
+        let rotations = [
+            "L68", "CCW30", "CW48", "L5", "R60", "CCW55", "L1", "L99", "CW14", "L82",
+        ];
+        assert_eq!(puzzle1_slice(50, &rotations), 3);
+        assert_eq!(puzzle2_slice(50, &rotations), 6);
+    }
+
+    #[test]
+    fn test_solve_both() {
+        let rotations = [
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+        ];
+        assert_eq!(solve_both(50, &rotations), (3, 6));
+        let extended = utils::str_slice_to_vec_string(&[
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82", "L32",
+            "R1000", "L1000", "R1", "R1000", "L234", "R32", "R1000", "R99", "R202"
+        ]);
+        assert_eq!(solve_both(50, &extended), (puzzle1_slice(50, &extended), 54));
+    }
+
+    #[test]
+    fn test_best_start() {
+        let rotations = utils::str_slice_to_vec_string(&[
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+        ]);
+        let (start, stops) = best_start(&rotations);
+        assert_eq!(Ok(stops), puzzle1(start, rotations.clone()));
+        assert!((0..=99).all(|s| puzzle1(s, rotations.clone()).unwrap() <= stops));
+        assert_eq!((start, stops), (50, 3));
+    }
+
+    #[test]
+    fn test_oriented() {
+        let rotations = [
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+        ];
+        assert_eq!(puzzle1_oriented(32, &rotations, false), 0);
+        assert_eq!(puzzle1_oriented(32, &rotations, true), 1);
+        // Mirroring the dial is the same as starting from the mirrored position
+        assert_eq!(puzzle1_oriented(32, &rotations, true), puzzle1_slice(68, &rotations));
+        assert_eq!(puzzle2_oriented(32, &rotations, true), puzzle2_slice(68, &rotations));
+    }
+
+    #[test]
+    fn test_analyze() {
+        let rotations = [
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+        ];
+        let report = analyze(50, &rotations);
+        assert_eq!(report.stops_on_zero, 3);
+        assert_eq!(report.crossings_of_zero, 6);
+        assert_eq!(report.final_position, 32);
+        assert_eq!(report.total_distance, 462);
+        assert!(report.to_string().starts_with("Puzzle 1: 3\nPuzzle 2: 6\n"));
+    }
+
+    #[test]
+    fn test_parsed() {
+        let rotations = [
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
+        ];
+        let parsed = parse_rotations(&rotations, false);
+        for start in 0..=99 {
+            assert_eq!(count_stops_parsed(start, &parsed), puzzle1_slice(start, &rotations));
+            assert_eq!(count_crossings_parsed(start, &parsed), puzzle2_slice(start, &rotations));
+        }
+        assert_eq!(count_stops_parsed(50, &parsed), 3);
+        assert_eq!(count_crossings_parsed(50, &parsed), 6);
+    }
+
+    #[test]
+    fn test_validate_rotations() {
+        assert_eq!(validate_rotations(&["L68", "CCW30", "R48"]), Ok(()));
+        let errors = validate_rotations(&["L68", "X30", "R48", "L", "R-5", "CW14"]).unwrap_err();
+        let indices: Vec<usize> = errors.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, vec![1, 3, 4]);
+        assert!(errors[0].1.contains("must start with"));
+        assert!(errors[1].1.contains("distance"));
+    }
+
+    #[test]
+    fn test_max_consecutive_zero_stops() {
+        assert_eq!(max_consecutive_zero_stops(50, &["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"]), 1);
+        // Full turns keep the dial on 0, so the run of three is only broken by R1
+        assert_eq!(max_consecutive_zero_stops(50, &["L50", "R100", "L200", "R1", "L1", "R100"]), 3);
+        assert_eq!(max_consecutive_zero_stops(50, &["R1", "L2"]), 0);
+    }
+
+    #[test]
+    fn test_crossings_per_rotation() {
+        let rotations = [
+            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82", "L32",
+            "R1000", "L1000", "R1", "R1000", "L234", "R32", "R1000", "R99", "R202",
+        ];
+        let crossings = crossings_per_rotation(50, &rotations);
+        assert_eq!(crossings.len(), rotations.len());
+        assert_eq!(crossings.iter().sum::<u64>(), 54);
+        assert_eq!(crossings[..10], [1, 0, 1, 0, 1, 1, 0, 1, 0, 1]);
+        assert_eq!(crossings[11], 10);
+    }
+
+    #[test]
+    fn test_errors() {
+        let rotations = utils::str_slice_to_vec_string(&["L68", "L30", "X12", "R4x"]);
+        let err = puzzle1(50, rotations.clone()).unwrap_err();
+        assert_eq!(err, Day1Error::InvalidDirection { index: 2, rotation: "X12".to_string() });
+        assert_eq!(err.to_string(), "line 3: bad rotation 'X12' (must start with L, R, CCW or CW)");
+        let rotations = utils::str_slice_to_vec_string(&["L68", "R4x"]);
+        assert_eq!(puzzle2(50, rotations), Err(Day1Error::InvalidDistance { index: 1, rotation: "R4x".to_string() }));
+        assert_eq!(puzzle2(100, vec![]), Err(Day1Error::InitOutOfRange { init_pos: 100, size: 100 }));
+    }
+
+    #[test]
+    fn test_dial_size() {
+        let rotations = ["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"];
+        assert_eq!(puzzle1_with_size(50, 100, &rotations), Ok(3));
+        assert_eq!(puzzle2_with_size(50, 100, &rotations), Ok(6));
+        // On a dial of 10, 5 -> 7 -> 0 -> 7 -> 0 -> 3 with 0, 3, 1, 2 and 1 crossings
+        let rotations = ["R2", "R23", "L13", "R13", "L17"];
+        assert_eq!(puzzle1_with_size(5, 10, &rotations), Ok(2));
+        assert_eq!(puzzle2_with_size(5, 10, &rotations), Ok(3 + 1 + 2 + 1));
+        assert_eq!(puzzle1_with_size(10, 10, &rotations), Err(Day1Error::InitOutOfRange { init_pos: 10, size: 10 }));
+    }
+
+    #[test]
+    fn test_positions() {
+        let rotations = ["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"];
+        let trail = positions(50, &rotations);
+        assert_eq!(trail, vec![82, 52, 0, 95, 55, 0, 99, 0, 14, 32]);
+        assert_eq!(trail.last().copied(), Some(analyze(50, &rotations).final_position));
+        assert_eq!(trail.iter().filter(|&&pos| pos == 0).count() as u64, puzzle1_slice(50, &rotations));
+    }
+
+    #[test]
+    fn test_crossings_by_direction() {
+        assert_eq!(crossings_by_direction(50, &["R1000"]), (0, 10));
+        assert_eq!(crossings_by_direction(50, &["L1000"]), (10, 0));
+        let rotations = ["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"];
+        let (left, right) = crossings_by_direction(50, &rotations);
+        assert_eq!((left, right), (4, 2));
+        assert_eq!(Ok(left + right), puzzle2(50, rotations.iter().map(|r| r.to_string()).collect()));
+    }
+
+    #[test]
+    fn test_puzzle1_reader() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n  \n\n";
+        assert_eq!(puzzle1_reader(50, input.as_bytes()), Ok(3));
+        assert_eq!(puzzle1_reader(50, "R50".as_bytes()), Ok(1));
+        assert_eq!(puzzle1_reader(50, "".as_bytes()), Ok(0));
+        // The index of a malformed rotation counts the blank lines, so it matches the line of the input
+        assert_eq!(
+            puzzle1_reader(50, "L68\n\nX5\n".as_bytes()),
+            Err(Day1Error::InvalidDirection { index: 2, rotation: "X5".to_string() })
         );
+        assert!(matches!(puzzle1_reader(50, &b"L68\n\xff\n"[..]), Err(Day1Error::ReadFailed { index: 1, .. })));
+        assert_eq!(puzzle1_reader(100, "R50".as_bytes()), Err(Day1Error::InitOutOfRange { init_pos: 100, size: 100 }));
     }
 }
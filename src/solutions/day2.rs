@@ -21,7 +21,7 @@
 /// 
 pub fn puzzle1(input: &str) -> u128 {
     let mut sum: u128 = 0;
-    let ranges: Vec<&str> = input.split(",").collect();
+    let ranges: Vec<&str> = split_ranges(input).collect();
     for range_str in ranges {
         let Ok(range) = NumberRange::from_str(range_str) else {
             //println!("skipping {}", range_str);
@@ -32,17 +32,16 @@ pub fn puzzle1(input: &str) -> u128 {
         if from_len == to_len && !from_len.is_multiple_of(2) {
             continue;
         }
+        // No number past the last candidate can be valid, so the rest of the range is skipped
+        let Some(last_candidate) = largest_repeated_halves(range.1) else {
+            continue;
+        };
         // Weird syntax: .. doesn't include the last number, but .= does :/
         for i in range.0..=range.1 {
-            let len: u32  = i.checked_ilog10().unwrap_or(0) + 1;
-            if !len.is_multiple_of(2) {
-                //println!("skipping {}", i);
-                continue;
+            if i > last_candidate {
+                break;
             }
-            let oe_half_len = 10_u64.pow(len/2);
-            let first_half: u64 = i / oe_half_len;
-            let second_half: u64 = i % oe_half_len;
-            if first_half != second_half {
+            if !is_repeated_halves(i) {
                 //println!("skipping {}", i);
                 continue;
             }
@@ -53,6 +52,90 @@ pub fn puzzle1(input: &str) -> u128 {
     sum
 }
 
+// The predicate of puzzle 1: the number has an even length and its first half of digits is equal to its second half
+fn is_repeated_halves(i: u64) -> bool {
+    let len: u32  = i.checked_ilog10().unwrap_or(0) + 1;
+    if !len.is_multiple_of(2) {
+        return false;
+    }
+    let oe_half_len = 10_u64.pow(len/2);
+    let first_half: u64 = i / oe_half_len;
+    let second_half: u64 = i % oe_half_len;
+    first_half == second_half
+}
+
+// Finds the largest number up to n whose first half of digits is equal to its second half, if there is any.
+// For an even length, the candidate repeats the first half of n, or the half just below it if that overshoots.
+// Otherwise, or if the half loses a digit, it's the largest number with two digits less, which is all 9s.
+fn largest_repeated_halves(n: u64) -> Option<u64> {
+    let len: u32 = n.checked_ilog10().unwrap_or(0) + 1;
+    if len.is_multiple_of(2) {
+        let oe_half_len = 10_u64.pow(len/2);
+        let first_half = n / oe_half_len;
+        if first_half * (oe_half_len + 1) <= n {
+            return Some(first_half * (oe_half_len + 1));
+        }
+        if first_half - 1 >= oe_half_len / 10 {
+            return Some((first_half - 1) * (oe_half_len + 1));
+        }
+    }
+    // The largest even length below len
+    let shorter_len = (len - 1) & !1;
+    if shorter_len == 0 {
+        None
+    } else {
+        Some(10_u64.pow(shorter_len) - 1)
+    }
+}
+
+/// # Invalid IDs (puzzle 1)
+///
+/// Returns every ID counted by puzzle 1 in ascending order, so that `puzzle1(input)` equals
+/// `invalid_ids(input).iter().map(|&id| u128::from(id)).sum()`. Like the puzzle, IDs within overlapping ranges are
+/// not deduplicated: an ID appears once for every range that contains it. Rather than checking every integer, the
+/// IDs of every even length L are built directly from the halves h as h * (10^(L/2) + 1).
+pub fn invalid_ids(input: &str) -> Vec<u64> {
+    let mut ids: Vec<u64> = vec![];
+    for range_str in split_ranges(input) {
+        let Ok(range) = NumberRange::from_str(range_str) else {
+            continue;
+        };
+        let start_len = range.0.checked_ilog10().unwrap_or(0) + 1;
+        let end_len = range.1.checked_ilog10().unwrap_or(0) + 1;
+        for len in (start_len..=end_len).filter(|len| len.is_multiple_of(2)) {
+            let pattern = 10_u64.pow(len / 2) + 1;
+            let first_half = range.0.div_ceil(pattern).max(10_u64.pow(len / 2 - 1));
+            let last_half = (range.1 / pattern).min(10_u64.pow(len / 2) - 1);
+            ids.extend((first_half..=last_half).map(|h| h * pattern));
+        }
+    }
+    ids.sort_unstable();
+    ids
+}
+
+/// # Puzzle 1 (fast)
+///
+/// Same as puzzle 1, but without walking every integer of the ranges, so that a range like 1-9999999999999999
+/// doesn't hang. A number of an even length L whose halves are equal is its first half h times 10^(L/2) + 1, so
+/// for every even length within a range, the matching numbers are an arithmetic series over the halves that keep
+/// the number within the range, which is summed in closed form with `sum_with_period`. The naive `puzzle1` is kept
+/// for cross-checking.
+pub fn puzzle1_fast(input: &str) -> u128 {
+    let mut sum: u128 = 0;
+    for range_str in split_ranges(input) {
+        let Ok(range) = NumberRange::from_str(range_str) else {
+            continue;
+        };
+        let start_len = range.0.checked_ilog10().unwrap_or(0) + 1;
+        let end_len = range.1.checked_ilog10().unwrap_or(0) + 1;
+        for len in (start_len..=end_len).filter(|len| len.is_multiple_of(2)) {
+            let lo = (range.0 as u128).max(10_u128.pow(len - 1));
+            let hi = (range.1 as u128).min(10_u128.pow(len) - 1);
+            sum += sum_with_period(lo, hi, len, len / 2);
+        }
+    }
+    sum
+}
 
 /// # Puzzle 2
 ///
@@ -68,17 +151,220 @@ pub fn puzzle1(input: &str) -> u128 {
 /// digits, and check if the original string appears in it. For example, given the number 565656, 
 /// concatenate it to form 565656565656 and then check if 565656 can be found in 6565656565.
 /// 
+/// That check is still kept in `is_repeated_pattern`, but walking every integer doesn't scale to very large
+/// ranges, so the invalid IDs of each range are now constructed and summed directly with
+/// `sum_repeated_pattern_in_range`.
+/// 
 pub fn puzzle2(input: &str) -> u128 {
-     let mut sum: u128 = 0;
-    let ranges: Vec<&str> = input.split(',').collect();
+    let mut sum: u128 = 0;
+    let ranges: Vec<&str> = split_ranges(input).collect();
     for range_str in ranges {
+        let Ok(range) = NumberRange::from_str(range_str) else {
+            continue;
+        };
+        sum += sum_repeated_pattern_in_range(range.0, range.1);
+    }
+    sum
+}
+
+/// Sums the numbers from start to end (inclusive) that are made of a sequence of digits repeated at least twice,
+/// without walking every integer. A number of length L that repeats a block of p digits, where p is a proper
+/// divisor of L, is the block times the pattern 10^(L-p) + ... + 10^p + 1 = (10^L - 1) / (10^p - 1), so for every
+/// period, the matching numbers are an arithmetic series over the blocks that keep the number within the range.
+///
+/// A number repeating with period p also repeats with every multiple of p that divides L, so it's enough to sum the
+/// periods L/q for every prime factor q of L. Those sets overlap though: a number repeating with two such periods
+/// also repeats with their greatest common divisor. Inclusion-exclusion over the subsets of prime factors removes
+/// the numbers counted more than once, e.g. for L = 6, the sums for the periods 3 and 2 both include the numbers
+/// repeating a single digit, which are subtracted once.
+pub fn sum_repeated_pattern_in_range(start: u64, end: u64) -> u128 {
+    if start > end {
+        return 0;
+    }
+    let start_len = start.checked_ilog10().unwrap_or(0) + 1;
+    let end_len = end.checked_ilog10().unwrap_or(0) + 1;
+    let mut sum: u128 = 0;
+    for len in start_len..=end_len {
+        // The part of the range with exactly len digits
+        let lo = (start as u128).max(10_u128.pow(len - 1));
+        let hi = (end as u128).min(10_u128.pow(len) - 1);
+        let primes: Vec<u32> = (2..=len).filter(|&q| len % q == 0 && (2..q).all(|d| q % d != 0)).collect();
+        // Every non-empty subset of the prime factors, as a bit mask
+        for mask in 1..(1u32 << primes.len()) {
+            let product: u32 = (0..primes.len()).filter(|b| mask & (1 << b) != 0).map(|b| primes[b]).product();
+            let period = len / product;
+            let series = sum_with_period(lo, hi, len, period);
+            if mask.count_ones() % 2 == 1 {
+                sum += series;
+            } else {
+                sum -= series;
+            }
+        }
+    }
+    sum
+}
+
+// Sums the numbers of len digits within lo to hi that repeat a block of period digits
+fn sum_with_period(lo: u128, hi: u128, len: u32, period: u32) -> u128 {
+    let pattern = (10_u128.pow(len) - 1) / (10_u128.pow(period) - 1);
+    let first_block = lo.div_ceil(pattern).max(10_u128.pow(period - 1));
+    let last_block = (hi / pattern).min(10_u128.pow(period) - 1);
+    if first_block > last_block {
+        return 0;
+    }
+    pattern * (first_block + last_block) * (last_block - first_block + 1) / 2
+}
+
+/// # Repeated blocks
+///
+/// Generalizes both puzzles: sums the IDs within the ranges that are made of a block of digits repeated exactly
+/// `min_repeats` times when `exact` is set (e.g. 123123123 for 3), or at least `min_repeats` times otherwise.
+/// Puzzle 1 is `sum_repeated(input, 2, true)` and puzzle 2 is `sum_repeated(input, 2, false)`.
+///
+/// Like `sum_repeated_pattern_in_range`, the numbers of every length L are summed in closed form per period. The
+/// periods are the divisors p of L that repeat L/p times, of which only the largest ones are needed, since a
+/// number repeating with period p also repeats with every multiple of p that divides L. The overlaps between
+/// them are removed with inclusion-exclusion, as a number repeating with two periods also repeats with their
+/// greatest common divisor.
+pub fn sum_repeated(input: &str, min_repeats: usize, exact: bool) -> u128 {
+    if min_repeats == 0 {
+        panic!("a number must be made of at least one repetition of a block");
+    }
+    let mut sum: u128 = 0;
+    for range_str in split_ranges(input) {
+        let Ok(range) = NumberRange::from_str(range_str) else {
+            continue;
+        };
+        let start_len = range.0.checked_ilog10().unwrap_or(0) + 1;
+        let end_len = range.1.checked_ilog10().unwrap_or(0) + 1;
+        for len in start_len..=end_len {
+            let lo = (range.0 as u128).max(10_u128.pow(len - 1));
+            let hi = (range.1 as u128).min(10_u128.pow(len) - 1);
+            let repeats_enough = |p: u32| {
+                let repeats = (len / p) as usize;
+                if exact { repeats == min_repeats } else { repeats >= min_repeats }
+            };
+            let periods: Vec<u32> = (1..=len).filter(|&p| len % p == 0 && repeats_enough(p)).collect();
+            let maximal: Vec<u32> = periods
+                .iter()
+                .copied()
+                .filter(|&p| !periods.iter().any(|&q| q != p && q % p == 0))
+                .collect();
+            // Every non-empty subset of the maximal periods, as a bit mask
+            for mask in 1..(1u32 << maximal.len()) {
+                let period = (0..maximal.len())
+                    .filter(|b| mask & (1 << b) != 0)
+                    .map(|b| maximal[b])
+                    .fold(0, gcd);
+                let series = sum_with_period(lo, hi, len, period);
+                if mask.count_ones() % 2 == 1 {
+                    sum += series;
+                } else {
+                    sum -= series;
+                }
+            }
+        }
+    }
+    sum
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// The string-doubling check of puzzle 2: a number is made of a repeated sequence of digits if it can be
+/// found in itself concatenated twice with the first and last digits trimmed.
+pub fn is_repeated_pattern(i: u64) -> bool {
+    let i_str: String = i.to_string();
+    let concat_str = format!("{}{}", i_str, i_str);
+    concat_str[1..concat_str.len()-1].contains(&i_str)
+}
+
+/// Checks the same predicate as puzzle 2 (a number made of a sequence of digits repeated at least twice) with the
+/// Knuth-Morris-Pratt (KMP) failure function instead of the string-doubling trick. The digits are extracted into a
+/// fixed-size array, so unlike the string-based check, nothing is allocated. The failure function holds, for every
+/// prefix of the digits, the length of the longest proper prefix that is also a suffix. If the whole number has such
+/// a border of length b, it has a period of len - b, and it consists of repetitions of that period when the period
+/// divides its length.
+pub fn has_repeating_pattern_kmp(n: u64) -> bool {
+    // u64::MAX has 20 digits
+    let mut digits = [0u8; 20];
+    let mut len = 0;
+    let mut rest = n;
+    loop {
+        digits[len] = (rest % 10) as u8;
+        len += 1;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    // The digits were extracted in reverse, but a number is periodic whichever way it is read
+    let digits = &digits[..len];
+    let mut failure = [0usize; 20];
+    let mut k = 0;
+    for i in 1..len {
+        while k > 0 && digits[i] != digits[k] {
+            k = failure[k - 1];
+        }
+        if digits[i] == digits[k] {
+            k += 1;
+        }
+        failure[i] = k;
+    }
+    let border = failure[len - 1];
+    border > 0 && len % (len - border) == 0
+}
+
+/// Checks whether a number reads the same forwards and backwards, by reversing its digits arithmetically rather than
+/// formatting it as a string. The reversed digits are collected in a u128, since reversing a 20 digit number may
+/// not fit in a u64.
+pub fn is_palindrome(n: u64) -> bool {
+    let mut reversed: u128 = 0;
+    let mut rest = n;
+    while rest > 0 {
+        reversed = reversed * 10 + (rest % 10) as u128;
+        rest /= 10;
+    }
+    reversed == n as u128
+}
+
+/// # Valid IDs (puzzle 1)
+///
+/// The complement of puzzle 1: returns the count and the sum of the IDs within the ranges that are valid, i.e. that
+/// aren't made of a sequence of digits repeated exactly twice.
+pub fn puzzle1_valid_sum(input: &str) -> (u64, u128) {
+    valid_ids(input, is_repeated_halves)
+}
+
+/// # Valid IDs (puzzle 2)
+///
+/// The complement of puzzle 2: returns the count and the sum of the IDs within the ranges that are valid, i.e. that
+/// aren't made of a sequence of digits repeated at least twice.
+pub fn puzzle2_valid_sum(input: &str) -> (u64, u128) {
+    valid_ids(input, is_repeated_pattern)
+}
+
+// Counts and sums the IDs within the ranges that don't match the predicate of invalid IDs
+fn valid_ids(input: &str, is_invalid: fn(u64) -> bool) -> (u64, u128) {
+    split_ranges(input)
+        .filter_map(|range_str| NumberRange::from_str(range_str).ok())
+        .flat_map(|range| range.0..=range.1)
+        .filter(|&i| !is_invalid(i))
+        .fold((0, 0), |(count, sum), i| (count + 1, sum + i as u128))
+}
+
+/// # Palindromic IDs
+///
+/// A variant of the puzzles, where the IDs to sum are the palindromic ones within the ranges, e.g. 1221 or 12321.
+pub fn puzzle_palindrome(input: &str) -> u128 {
+    let mut sum: u128 = 0;
+    for range_str in split_ranges(input) {
         let Ok(range) = NumberRange::from_str(range_str) else {
             continue;
         };
         for i in range.0..=range.1 {
-            let i_str: String = i.to_string();
-            let concat_str = format!("{}{}", i_str, i_str);
-            if concat_str[1..concat_str.len()-1].contains(&i_str) {
+            if is_palindrome(i) {
                 sum += i as u128;
             }
         }
@@ -86,6 +372,48 @@ pub fn puzzle2(input: &str) -> u128 {
     sum
 }
 
+/// # Invalid IDs
+///
+/// Lazily yields the IDs that are invalid by the rules of puzzle 2, in the order of the ranges. The ranges are parsed
+/// as the iterator advances and the numbers within a range are only checked when the next ID is requested, so the
+/// iterator can feed a larger pipeline without collecting the IDs first.
+pub fn matching_ids_iter(input: &str) -> impl Iterator<Item = u64> {
+    split_ranges(input)
+        .filter_map(|range_str| NumberRange::from_str(range_str).ok())
+        .flat_map(|range| range.0..=range.1)
+        .filter(|&i| is_repeated_pattern(i))
+}
+
+/// # Largest invalid ID
+///
+/// Returns the largest ID across all ranges that is invalid by the rules of puzzle 2, or `None` if there
+/// are no invalid IDs. Each range is walked from the top down, so the scan of a range stops at its first
+/// match or once it reaches the largest match found in an earlier range.
+pub fn largest_invalid(input: &str) -> Option<u64> {
+    let mut largest: Option<u64> = None;
+    for range_str in split_ranges(input) {
+        let Ok(range) = NumberRange::from_str(range_str) else {
+            continue;
+        };
+        let floor = match largest {
+            Some(l) if l >= range.1 => continue,
+            Some(l) => range.0.max(l + 1),
+            None => range.0,
+        };
+        if let Some(i) = (floor..=range.1).rev().find(|&i| is_repeated_pattern(i)) {
+            largest = Some(i);
+        }
+    }
+    largest
+}
+
+// The ranges may be separated by commas, newlines or any other whitespace, or a mix of them
+fn split_ranges(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+}
+
 #[derive(PartialEq, Debug)]
 struct NumberRange(u64, u64);
 
@@ -93,7 +421,7 @@ impl NumberRange {
     pub fn from_str(input: &str) -> Result<Self, &str> {
         // .ok_or() defines what error to return.
         // Adding ? to the end means return immediately in case of error, similar to if err != nil { ... } in Go
-        let (from_str, to_str) = input.split_once("-").ok_or("number range must be delimited by a -")?;
+        let (from_str, to_str) = input.trim().split_once("-").ok_or("number range must be delimited by a -")?;
         // Here, we can't use .ok_or(), because .parse() returns a Result<F, F::Err> whereas .split_once() returns an Option<&'_str,&'_str>.
         // The alternative approach is to use .map_err() with a Rust closure (anonymous function) that returns a static error.
         // I tried finding a quick way to retain the original message, but couldn't, so I just went with a static message.
@@ -102,8 +430,8 @@ impl NumberRange {
         if from_str.starts_with('0') || to_str.starts_with('0') {
             return Err("numbers in the ranger must not begin with a 0");
         }
-        let from: u64 = from_str.parse().map_err(|_| "numbers in the range must be integers")?;
-        let to: u64 = to_str.parse().map_err(|_| "numbers in the range must be integers")?;
+        let from: u64 = from_str.parse().map_err(parse_error)?;
+        let to: u64 = to_str.parse().map_err(parse_error)?;
         if to <= from {
             return Err("the second number in the range must be larger than the first");
         }
@@ -111,6 +439,15 @@ impl NumberRange {
     }
 }
 
+// Tells numbers that are too large for a u64 apart from text that isn't a number at all, since a 20 digit ID
+// would otherwise be rejected as if it weren't an integer
+fn parse_error(err: std::num::ParseIntError) -> &'static str {
+    match err.kind() {
+        std::num::IntErrorKind::PosOverflow => "numbers in the range must not exceed 18446744073709551615 (u64::MAX)",
+        _ => "numbers in the range must be integers",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +478,24 @@ mod tests {
             assert!(NumberRange::from_str("1-02").is_err());
         }
 
+        #[test]
+        fn rejects_overflowing_numbers() {
+            assert_eq!(
+                NumberRange::from_str("1-99999999999999999999"),
+                Err("numbers in the range must not exceed 18446744073709551615 (u64::MAX)")
+            );
+            assert_eq!(NumberRange::from_str("1-x"), Err("numbers in the range must be integers"));
+            assert_eq!(
+                NumberRange::from_str("1-18446744073709551615"),
+                Ok(NumberRange(1, u64::MAX))
+            );
+        }
+
+        #[test]
+        fn trims_surrounding_whitespace() {
+            assert_eq!(NumberRange::from_str(" 95-115\n"), Ok(NumberRange(95, 115)));
+        }
+
         #[test]
         fn rejects_invalid_ordering() {
             assert!(NumberRange::from_str("2-1").is_err());
@@ -164,9 +519,147 @@ mod tests {
         assert_eq!(puzzle1(TEST_INPUT), 1227775554);
     }
 
+    #[test]
+    fn test_puzzle1_fast() {
+        assert_eq!(puzzle1_fast(TEST_INPUT), 1227775554);
+        assert_eq!(puzzle1_fast("1-9999999"), puzzle1("1-9999999"));
+        assert_eq!(puzzle1_fast("1-9999999999999999"), 495495495950040900040950);
+    }
+
+    #[test]
+    fn test_sum_repeated() {
+        assert_eq!(sum_repeated(TEST_INPUT, 2, true), puzzle1(TEST_INPUT));
+        assert_eq!(sum_repeated(TEST_INPUT, 2, false), puzzle2(TEST_INPUT));
+        // 111, 999, 222222, 565656 and 824824824 repeat a block exactly 3 times
+        assert_eq!(sum_repeated(TEST_INPUT, 3, true), 111 + 222222 + 565656 + 824824824 + 999);
+        assert_eq!(sum_repeated("123123120-123123130", 3, true), 123123123);
+        assert_eq!(sum_repeated(TEST_INPUT, 3, false), 2946825933);
+        assert_eq!(sum_repeated("1-100000", 4, false), 1111 * 45 + 11111 * 45);
+    }
+
+    #[test]
+    fn test_invalid_ids() {
+        assert_eq!(
+            invalid_ids(TEST_INPUT),
+            vec![11, 22, 99, 1010, 222222, 446446, 38593859, 1188511885]
+        );
+        let sum: u128 = invalid_ids(TEST_INPUT).iter().map(|&id| u128::from(id)).sum();
+        assert_eq!(sum, puzzle1(TEST_INPUT));
+        // Overlapping ranges keep an ID once per range
+        assert_eq!(invalid_ids("10-33,20-44"), vec![11, 22, 22, 33, 33, 44]);
+    }
+
+    #[test]
+    fn test_whitespace_around_ranges() {
+        assert_eq!(puzzle1("11-22, 95-115"), 11 + 22 + 99);
+        assert_eq!(puzzle2("11-22, 95-115"), 11 + 22 + 99 + 111);
+        let wrapped = format!("{}\n", TEST_INPUT.replace(",", ",\n "));
+        assert_eq!(puzzle1(&wrapped), 1227775554);
+        assert_eq!(puzzle2(&wrapped), 4174379265);
+    }
+
     #[test]
     fn test_puzzle2() {
         assert_eq!(puzzle2(TEST_INPUT), 4174379265);
     }
-}
 
+    #[test]
+    fn test_puzzle1_early_break() {
+        assert_eq!(largest_repeated_halves(1012), Some(1010));
+        assert_eq!(largest_repeated_halves(1009), Some(99));
+        assert_eq!(largest_repeated_halves(115), Some(99));
+        assert_eq!(largest_repeated_halves(9), None);
+        // The early break must not change the sum compared to checking every number in the range
+        for input in [TEST_INPUT, "10-9999", "95-1012", "1000-1009", "5-10", "123-124000", "9899-100000"] {
+            let expected: u128 = split_ranges(input)
+                .filter_map(|r| NumberRange::from_str(r).ok())
+                .flat_map(|r| r.0..=r.1)
+                .filter(|&i| {
+                    let s = i.to_string();
+                    s.len() % 2 == 0 && s[..s.len() / 2] == s[s.len() / 2..]
+                })
+                .map(u128::from)
+                .sum();
+            assert_eq!(puzzle1(input), expected, "mismatch for {}", input);
+        }
+    }
+
+    #[test]
+    fn test_separators() {
+        let newline_separated = TEST_INPUT.replace(',', "\n") + "\n";
+        assert_eq!(puzzle1(&newline_separated), 1227775554);
+        assert_eq!(puzzle2(&newline_separated), 4174379265);
+        let mixed = TEST_INPUT.replacen(',', " ", 3).replacen(',', ",\n", 3).replacen(',', "\t", 2);
+        assert_eq!(puzzle1(&mixed), 1227775554);
+        assert_eq!(puzzle2(&mixed), 4174379265);
+    }
+
+    #[test]
+    fn test_kmp_matches_string_doubling() {
+        for i in 1..100000 {
+            assert_eq!(has_repeating_pattern_kmp(i), is_repeated_pattern(i), "mismatch for {}", i);
+        }
+        assert!(has_repeating_pattern_kmp(1212121212121212));
+        assert!(!has_repeating_pattern_kmp(u64::MAX));
+    }
+
+    #[test]
+    fn test_matching_ids_iter() {
+        let first_five: Vec<u64> = matching_ids_iter(TEST_INPUT).take(5).collect();
+        assert_eq!(first_five, vec![11, 22, 99, 111, 999]);
+        assert_eq!(matching_ids_iter(TEST_INPUT).map(u128::from).sum::<u128>(), puzzle2(TEST_INPUT));
+    }
+
+    #[test]
+    fn test_largest_invalid() {
+        assert_eq!(largest_invalid(TEST_INPUT), Some(2121212121));
+        assert_eq!(largest_invalid("95-115,11-22"), Some(111));
+        assert_eq!(largest_invalid("12-20"), None);
+    }
+
+    #[test]
+    fn test_palindrome() {
+        assert!(is_palindrome(1221));
+        assert!(is_palindrome(12321));
+        assert!(is_palindrome(7));
+        assert!(!is_palindrome(1231));
+        assert!(!is_palindrome(u64::MAX));
+        assert_eq!(puzzle_palindrome("1200-1300,12300-12400"), 1221 + 12321);
+        assert_eq!(puzzle_palindrome("95-115"), 99 + 101 + 111);
+    }
+
+    #[test]
+    fn test_valid_sum() {
+        let (total_count, total_sum) = split_ranges(TEST_INPUT)
+            .filter_map(|r| NumberRange::from_str(r).ok())
+            .fold((0, 0), |(count, sum), r| (count + r.1 - r.0 + 1, sum + (r.0..=r.1).map(u128::from).sum::<u128>()));
+        let (valid_count, valid_sum) = puzzle1_valid_sum(TEST_INPUT);
+        assert_eq!(valid_sum + puzzle1(TEST_INPUT), total_sum);
+        assert_eq!(valid_count + 8, total_count);
+        let (valid_count, valid_sum) = puzzle2_valid_sum(TEST_INPUT);
+        assert_eq!(valid_sum + puzzle2(TEST_INPUT), total_sum);
+        assert_eq!(valid_count as usize + matching_ids_iter(TEST_INPUT).count(), total_count as usize);
+        assert_eq!(puzzle1_valid_sum("95-115"), (20, 2205 - 99));
+    }
+
+    #[test]
+    fn test_sum_repeated_pattern_in_range() {
+        let brute_force = |start: u64, end: u64| (start..=end).filter(|&i| is_repeated_pattern(i)).map(u128::from).sum::<u128>();
+        let sample: u128 = split_ranges(TEST_INPUT)
+            .filter_map(|r| NumberRange::from_str(r).ok())
+            .map(|r| brute_force(r.0, r.1))
+            .sum();
+        assert_eq!(sample, 4174379265);
+        assert_eq!(puzzle2(TEST_INPUT), sample);
+        for (start, end) in [(1, 10_000), (95, 115), (99_990, 1_000_100), (111_111, 111_111), (5, 4)] {
+            assert_eq!(sum_repeated_pattern_in_range(start, end), brute_force(start, end), "{}-{}", start, end);
+        }
+        // The largest repeated number that fits in a u64 is 1844674407 twice, which is below this range
+        assert_eq!(sum_repeated_pattern_in_range(u64::MAX - 10, u64::MAX), 0);
+        // 19 nines are the only repeated number in this range, repeating the digit 9
+        assert_eq!(
+            sum_repeated_pattern_in_range(9_999_999_999_999_999_990, 9_999_999_999_999_999_999),
+            9_999_999_999_999_999_999
+        );
+    }
+}
@@ -2,6 +2,9 @@
 
 //! # Day 3: Lobby ([challenge description](https://adventofcode.com/2025/day/3))
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 /// # Puzzle 1
 /// 
 /// ## Summary
@@ -20,35 +23,36 @@
 pub fn puzzle1(input: &str) -> u128 {
     input
         .lines()
-        .filter_map(|s| {
-            let digits: Vec<u32> = s
-                .chars()
-                .filter_map(|c| c.to_digit(10))
-                .collect();
-            let mut arr: [u32; 2] = [0, 0];
-            let last_i: usize = digits.len()-1;
-            let d1_max_i: usize = last_i - 1;
-            for i in 0..=last_i {
-                let digit: u32 = digits[i];
-                if digit > arr[0] && i <= d1_max_i {
-                    arr[0] = digit;
-                    arr[1] = 0;
-                } else if digit > arr[1] {
-                    arr[1] = digit;
-                }
-            }
-            match format!("{}{}", arr[0], arr[1]).parse::<u128>() {
-                Ok(num) => Some(num),
-                Err(e) => {
-                    println!("failed to parse integer from number string: {}", e);
-                    Some(0)
-                }
-            }
-        })
-        .map(u128::from)
+        .filter_map(puzzle1_line)
         .sum()
 }
 
+// The largest two-digit number in a single line, or None if the line has too few digits
+fn puzzle1_line(s: &str) -> Option<u128> {
+    let digits: Vec<u32> = s
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect();
+    // A line without enough digits can't form a number, so it doesn't contribute to the sum
+    if digits.len() < 2 {
+        return None;
+    }
+    let mut arr: [u32; 2] = [0, 0];
+    let last_i: usize = digits.len()-1;
+    let d1_max_i: usize = last_i - 1;
+    for i in 0..=last_i {
+        let digit: u32 = digits[i];
+        if digit > arr[0] && i <= d1_max_i {
+            arr[0] = digit;
+            arr[1] = 0;
+        } else if digit > arr[1] {
+            arr[1] = digit;
+        }
+    }
+    // The string only ever contains digits, but if parsing fails, skip the line rather than counting it as 0
+    format!("{}{}", arr[0], arr[1]).parse::<u128>().ok()
+}
+
 const BATTERY_BANK_SIZE: usize = 12;
 
 /// # Puzzle 2
@@ -69,46 +73,401 @@ const BATTERY_BANK_SIZE: usize = 12;
 pub fn puzzle2(input: &str) -> u128 {
     input
         .lines()
-        .filter_map(|s| {
-            let digits: Vec<u32> = s
-                .chars()
-                .filter_map(|c| c.to_digit(10))
-                .collect();
-            let mut arr: [u32; BATTERY_BANK_SIZE] = [0; BATTERY_BANK_SIZE];
-            let last_i: usize = digits.len()-1;
-            let dx_max_i: [usize; BATTERY_BANK_SIZE] = std::array::from_fn(|i| {
-                let max: i32 = last_i as i32 - (BATTERY_BANK_SIZE as i32 - i as i32 - 1);
-                if max >= 0 {
-                    max as usize
-                } else {
-                    0
+        .filter_map(puzzle2_line)
+        .sum()
+}
+
+// The largest number of BATTERY_BANK_SIZE digits in a single line, or None if the line has too few digits
+fn puzzle2_line(s: &str) -> Option<u128> {
+    let digits: Vec<u32> = s
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect();
+    if digits.len() < BATTERY_BANK_SIZE {
+        return None;
+    }
+    let mut arr: [u32; BATTERY_BANK_SIZE] = [0; BATTERY_BANK_SIZE];
+    let last_i: usize = digits.len()-1;
+    let dx_max_i: [usize; BATTERY_BANK_SIZE] = std::array::from_fn(|i| {
+        let max: i32 = last_i as i32 - (BATTERY_BANK_SIZE as i32 - i as i32 - 1);
+        if max >= 0 {
+            max as usize
+        } else {
+            0
+        }
+    });
+    let last_j: usize = BATTERY_BANK_SIZE-1;
+    'outer: for i in 0..=last_i {
+        let digit: u32 = digits[i];
+        for j in 0..=last_j {
+            if digit > arr[j] && i <= dx_max_i[j] {
+                arr[j] = digit;
+                if j < last_j {
+                    arr[j+1] = 0;
+                    continue 'outer;
                 }
-            });
-            let last_j: usize = BATTERY_BANK_SIZE-1;
-            'outer: for i in 0..=last_i {
-                let digit: u32 = digits[i];
-                for j in 0..=last_j {
-                    if digit > arr[j] && i <= dx_max_i[j] {
-                        arr[j] = digit;
-                        if j < last_j {
-                            arr[j+1] = 0;
-                            continue 'outer;
-                        }
-                    }
+            }
+        }
+    }
+    arr.iter().map(|&num| num.to_string()).collect::<String>().parse::<u128>().ok()
+}
+
+/// # Puzzle 2 (parallel)
+///
+/// Same as puzzle 2, but the lines are processed across threads. Every line is independent of the others,
+/// so the per-line numbers can be computed in any order and summed at the end.
+#[cfg(feature = "parallel")]
+#[must_use]
+pub fn puzzle2_parallel(input: &str) -> u128 {
+    input
+        .par_lines()
+        .filter_map(puzzle2_line)
+        .sum()
+}
+
+// Selects the positions of the bank_size digits forming the largest number, using the same approach as puzzle 2.
+// Unlike puzzle 2, replacing a digit resets all the following digits, and a digit that hasn't been selected yet is
+// None rather than 0, so that a 0 digit is still selected. A position stays None if the line has too few digits.
+fn select_positions(digits: &[u32], bank_size: usize) -> Vec<Option<usize>> {
+    let mut arr: Vec<Option<usize>> = vec![None; bank_size];
+    'outer: for (i, &digit) in digits.iter().enumerate() {
+        // Digit j may only be replaced if there are enough digits left to complete the number
+        let remaining = digits.len() - i;
+        for j in 0..bank_size {
+            let beats_selected = arr[j].is_none_or(|k| digit > digits[k]);
+            if beats_selected && remaining >= bank_size - j {
+                arr[j] = Some(i);
+                arr[j + 1..].fill(None);
+                continue 'outer;
+            }
+        }
+    }
+    arr
+}
+
+fn parse_digits(line: &str, radix: u32) -> Vec<u32> {
+    line.chars()
+        .filter_map(|c| c.to_digit(radix))
+        .collect()
+}
+
+// Forms the largest number of bank_size digits from a line of digits in the given radix. The digits are combined
+// arithmetically rather than by parsing a string, so no radix-specific formatting is needed.
+fn largest_number(line: &str, bank_size: usize, radix: u32) -> u128 {
+    let digits = parse_digits(line, radix);
+    select_positions(&digits, bank_size)
+        .iter()
+        .map(|p| p.map_or(0, |k| digits[k]))
+        .fold(0u128, |acc, d| acc * u128::from(radix) + u128::from(d))
+}
+
+// Same as largest_number, but like in the puzzles, a line with fewer digits than the bank size can't form a number
+fn largest_full_number(line: &str, bank_size: usize, radix: u32) -> Option<u128> {
+    if parse_digits(line, radix).len() < bank_size {
+        return None;
+    }
+    Some(largest_number(line, bank_size, radix))
+}
+
+/// # Puzzle 1 (any radix)
+///
+/// Same as puzzle 1, but the digits are in the given radix (2 to 36), e.g. 16 for lines of hexadecimal
+/// digits such as 1A9F3. The largest number of each line is formed in that radix and the sum is returned.
+#[must_use]
+pub fn puzzle1_radix(input: &str, radix: u32) -> u128 {
+    input
+        .lines()
+        .filter_map(|s| largest_full_number(s, 2, radix))
+        .sum()
+}
+
+/// # Puzzle 2 (any radix)
+///
+/// Same as puzzle 2, but the digits are in the given radix (2 to 36), like in `puzzle1_radix`.
+#[must_use]
+pub fn puzzle2_radix(input: &str, radix: u32) -> u128 {
+    input
+        .lines()
+        .filter_map(|s| largest_full_number(s, BATTERY_BANK_SIZE, radix))
+        .sum()
+}
+
+// Formats a number in the given radix with uppercase digits, like {:X} does for radix 16
+fn format_radix(mut value: u128, radix: u32) -> String {
+    let mut digits = vec![];
+    loop {
+        let digit = char::from_digit((value % u128::from(radix)) as u32, radix).expect("radix must be between 2 and 36");
+        digits.push(digit.to_ascii_uppercase());
+        value /= u128::from(radix);
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+/// # Puzzle 2 (any radix, formatted)
+///
+/// Same as `puzzle2_radix`, but rather than summing them, returns the largest number of every line formatted
+/// in the same radix with uppercase digits, e.g. `FEDCBA987654` for radix 16. Like `puzzle2_radix`, lines with fewer
+/// digits than the bank size are skipped.
+#[must_use]
+pub fn puzzle2_radix_formatted(input: &str, radix: u32) -> Vec<String> {
+    input
+        .lines()
+        .filter_map(|s| largest_full_number(s, BATTERY_BANK_SIZE, radix))
+        .map(|number| format_radix(number, radix))
+        .collect()
+}
+
+/// # Puzzle 2 (inline bank size)
+///
+/// Same as puzzle 2, but a line may start with its own bank size followed by a colon, e.g. `5:987654321111111` forms
+/// the largest 5-digit number of the digits after the colon. Lines without the prefix, or with a prefix that isn't a
+/// bank size from 1 to 38 (the most digits that always fit in a u128), use the bank size of puzzle 2. Like in puzzle 2,
+/// lines with fewer digits than their bank size are skipped.
+#[must_use]
+pub fn puzzle2_inline_size(input: &str) -> u128 {
+    input
+        .lines()
+        .filter_map(|s| match s.split_once(':') {
+            Some((k, digits)) => {
+                let k = k
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|k| (1..=U128_DIGITS).contains(k))
+                    .unwrap_or(BATTERY_BANK_SIZE);
+                largest_full_number(digits, k, 10)
+            }
+            None => largest_full_number(s, BATTERY_BANK_SIZE, 10),
+        })
+        .sum()
+}
+
+// Every number of up to 38 digits fits in a u128, whose maximum has 39 digits
+const U128_DIGITS: usize = 38;
+
+/// # Largest number (any bank size)
+///
+/// Forms the largest number of `bank_size` digits in a line like puzzle 2, and returns it in decimal. Up to 38 digits,
+/// the number is formed in a u128 like the puzzles. Larger banks would overflow it, so their selected digits are
+/// concatenated as characters instead, without the leading zeros. Returns `None` if the line has too few digits.
+#[must_use]
+pub fn largest_number_decimal(line: &str, bank_size: usize) -> Option<String> {
+    let digits = parse_digits(line, 10);
+    if digits.len() < bank_size {
+        return None;
+    }
+    let selected = select_positions(&digits, bank_size)
+        .iter()
+        .map(|p| p.map_or(0, |k| digits[k]))
+        .collect::<Vec<u32>>();
+    if bank_size <= U128_DIGITS {
+        return Some(selected.iter().fold(0u128, |acc, &d| acc * 10 + u128::from(d)).to_string());
+    }
+    let number: String = selected
+        .iter()
+        .skip_while(|&&d| d == 0)
+        .map(|&d| char::from_digit(d, 10).unwrap())
+        .collect();
+    Some(if number.is_empty() { "0".to_string() } else { number })
+}
+
+// Forms the largest number of bank_size digits from a line, either from all of its digits, or if separators are
+// respected, from the digits of a single group of the line, taking the largest number across the groups.
+// Groups (or lines) with too few digits are skipped.
+fn largest_number_in_groups(line: &str, bank_size: usize, respect_separators: bool) -> Option<u128> {
+    let groups: Vec<&str> = if respect_separators {
+        line.split_whitespace().collect()
+    } else {
+        vec![line]
+    };
+    groups
+        .into_iter()
+        .map(|group| parse_digits(group, 10))
+        .filter(|digits| digits.len() >= bank_size)
+        .map(|digits| {
+            select_positions(&digits, bank_size)
+                .iter()
+                .map(|p| p.map_or(0, |k| digits[k]))
+                .fold(0u128, |acc, d| acc * 10 + u128::from(d))
+        })
+        .max()
+}
+
+/// # Puzzle 1 (separators)
+///
+/// By default, the puzzles drop every character that isn't a digit, so a line like `12 34` is read as the digits 1234,
+/// and the number formed for it may take digits from both sides of the space. With `respect_separators`, whitespace
+/// instead splits a line into groups of contiguous digits, the largest number is formed within each group on its own,
+/// and the largest of those is the number of the line. Without it, the result is the same as puzzle 1.
+#[must_use]
+pub fn puzzle1_separated(input: &str, respect_separators: bool) -> u128 {
+    input
+        .lines()
+        .filter_map(|s| largest_number_in_groups(s, 2, respect_separators))
+        .sum()
+}
+
+/// # Puzzle 2 (separators)
+///
+/// Same as puzzle 2, with the same handling of whitespace as `puzzle1_separated`.
+#[must_use]
+pub fn puzzle2_separated(input: &str, respect_separators: bool) -> u128 {
+    input
+        .lines()
+        .filter_map(|s| largest_number_in_groups(s, BATTERY_BANK_SIZE, respect_separators))
+        .sum()
+}
+
+/// # Largest divisible number
+///
+/// Finds the largest number formed by taking `k` of the decimal digits in order (leading zeros allowed) that is
+/// divisible by `m`, or `None` if no such number exists or `m` is 0.
+///
+/// The greedy approach of puzzle 2 doesn't work here, since the best digit for a position depends on the remainder
+/// left for the digits after it. Instead, build a table from the end of the digits, where `best[j][r]` is the largest
+/// number of `j` digits taken from the current position onward with a remainder of `r`. Each position either skips
+/// its digit or prepends it to a number of `j - 1` digits, whose remainder then shifts by `digit * 10^(j-1)`. Numbers
+/// that don't fit in a u64 are ignored. The time complexity is O(n * k * m).
+#[must_use]
+pub fn largest_divisible(digits: &[u32], k: usize, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let m_len = m as usize;
+    // best[j][r] for the digits after the current position
+    let mut best: Vec<Vec<Option<u64>>> = vec![vec![None; m_len]; k + 1];
+    best[0][0] = Some(0);
+    for (i, &digit) in digits.iter().enumerate().rev() {
+        // Taking the digit at position i leaves at most digits.len() - i digits to choose from
+        for j in (1..=k.min(digits.len() - i)).rev() {
+            let Some(place) = 10u64.checked_pow(j as u32 - 1) else {
+                continue;
+            };
+            let Some(prefix) = place.checked_mul(u64::from(digit)) else {
+                continue;
+            };
+            // Updating j in place is safe, since j - 1 hasn't been updated for this position yet
+            for r in 0..m_len {
+                let Some(rest) = best[j - 1][r] else {
+                    continue;
+                };
+                let Some(number) = prefix.checked_add(rest) else {
+                    continue;
+                };
+                let remainder = (number % m) as usize;
+                if best[j][remainder].is_none_or(|b| number > b) {
+                    best[j][remainder] = Some(number);
                 }
             }
-            match arr.iter().map(|&num| num.to_string()).collect::<String>().parse::<u128>() {
-                Ok(num) => Some(num),
-                Err(e) => {
-                    println!("failed to parse integer from number string: {}", e);
-                    Some(0)
+        }
+    }
+    best[k][0]
+}
+
+// Compares two strings of decimal digits by their numeric value, ignoring leading zeros
+fn cmp_numeric(a: &str, b: &str) -> std::cmp::Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+// Compares two strings of decimal digits that follow a non-zero digit, so their leading zeros are kept: a longer
+// string always forms a larger number once it is appended, e.g. 105 is larger than 19
+fn cmp_padded(a: &str, b: &str) -> std::cmp::Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+// Keeps the larger of two optional concatenations
+fn max_concat(skip: Option<String>, take: Option<String>, cmp: fn(&str, &str) -> std::cmp::Ordering) -> Option<String> {
+    match (skip, take) {
+        (Some(skip), Some(take)) => Some(std::cmp::max_by(skip, take, |a, b| cmp(a, b))),
+        (skip, take) => skip.or(take),
+    }
+}
+
+/// # Puzzle 2 (multi-digit tokens)
+///
+/// ## Summary
+/// In this variant, each line consists of whitespace-separated tokens of one or more digits, e.g. `12 7 345 9`.
+/// Pick exactly `k` tokens in the same order, such that their concatenation forms the largest number.
+///
+/// ## Solution
+/// Unlike single digits, tokens can't be compared on their own: 9 is smaller than 50, yet 950 is larger than 509.
+/// Picking the tokens greedily is therefore not enough, since the best token for a position depends on what can
+/// follow it. Instead, build a table from the end of the line, where `best[i][j]` is the largest concatenation of
+/// `j` tokens taken from the i-th token onward. Each entry either skips the i-th token or prepends it to the best
+/// concatenation of `j - 1` tokens after it, whichever is larger.
+///
+/// Tokens may have leading zeros though, which only don't count before the first non-zero digit: after 1, the
+/// suffix 05 forms 105, which is larger than the 19 formed by the suffix 9. So `best` compares the concatenations
+/// as they are, for when a non-zero digit was already taken, while `leading` compares them by their numeric value,
+/// for when only zeros were taken so far, and continues with `best` once a token with a non-zero digit is taken.
+/// Like in puzzle 2, lines that can't form a number are
+/// skipped: lines with fewer than `k` tokens, a token that isn't made of digits, or a number too large for a u128.
+#[must_use]
+pub fn puzzle2_tokens_concat(input: &str, k: usize) -> u128 {
+    input
+        .lines()
+        .filter_map(|s| {
+            let tokens: Vec<&str> = s.split_whitespace().collect();
+            if k == 0 || tokens.len() < k || !tokens.iter().all(|t| t.bytes().all(|b| b.is_ascii_digit())) {
+                return None;
+            }
+            // best[i][j] and leading[i][j] are None when there are fewer than j tokens left from index i
+            let mut best: Vec<Vec<Option<String>>> = vec![vec![None; k + 1]; tokens.len() + 1];
+            let mut leading = best.clone();
+            for i in 0..=tokens.len() {
+                best[i][0] = Some(String::new());
+                leading[i][0] = Some(String::new());
+            }
+            for i in (0..tokens.len()).rev() {
+                let only_zeros = tokens[i].bytes().all(|b| b == b'0');
+                for j in 1..=k {
+                    let take = best[i + 1][j - 1].as_ref().map(|rest| format!("{}{}", tokens[i], rest));
+                    best[i][j] = max_concat(best[i + 1][j].clone(), take, cmp_padded);
+                    let rest = if only_zeros { &leading[i + 1][j - 1] } else { &best[i + 1][j - 1] };
+                    let take = rest.as_ref().map(|rest| format!("{}{}", tokens[i], rest));
+                    leading[i][j] = max_concat(leading[i + 1][j].clone(), take, cmp_numeric);
                 }
             }
+            leading[0][k].take()?.parse::<u128>().ok()
         })
-        .map(u128::from)
         .sum()
 }
 
+/// # Puzzle 2 (validated)
+///
+/// Same as puzzle 2, but verifies the number puzzle 2 forms for every line against an independent selection of
+/// exactly `BATTERY_BANK_SIZE` digits in the order of the line, which keeps the positions of leading zeros too. A line
+/// with fewer digits than the bank size has no such selection, and a line for which puzzle 2 forms a different number
+/// than the selected digits results in an error naming the line. Empty lines are skipped.
+pub fn puzzle2_validated(input: &str) -> Result<u128, String> {
+    let mut total = 0u128;
+    for (line_i, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let digits = parse_digits(line, 10);
+        let positions: Vec<usize> = select_positions(&digits, BATTERY_BANK_SIZE)
+            .into_iter()
+            .collect::<Option<_>>()
+            .ok_or(format!("line {} has fewer than {} digits", line_i + 1, BATTERY_BANK_SIZE))?;
+        let selected = positions
+            .iter()
+            .fold(0u128, |acc, &k| acc * 10 + u128::from(digits[k]));
+        match puzzle2_line(line) {
+            Some(number) if number == selected => total += number,
+            formed => {
+                return Err(format!(
+                    "line {} formed {:?} in puzzle 2, but its selected digits form {}",
+                    line_i + 1, formed, selected
+                ));
+            }
+        }
+    }
+    Ok(total)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +486,174 @@ mod tests {
     fn test_puzzle2() {
         assert_eq!(puzzle2(TEST_INPUT), 3121910778619);
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_puzzle2_parallel() {
+        assert_eq!(puzzle2_parallel(TEST_INPUT), 3121910778619);
+        let large: String = (0..10_000u64)
+            .map(|i| format!("{:0>20}\n", i.wrapping_mul(6364136223846793005) % 10u64.pow(19)))
+            .collect();
+        assert_eq!(puzzle2_parallel(&large), puzzle2(&large));
+    }
+
+    #[test]
+    fn test_pathological_lines() {
+        // Lines without enough digits are skipped instead of panicking or being counted as 0
+        let input = format!("\nabc\n7\n{}", TEST_INPUT);
+        assert_eq!(puzzle1(&input), 357);
+        assert_eq!(puzzle2(&input), 3121910778619);
+        // The line helpers skip these lines by returning None, rather than printing an error and returning 0
+        for line in ["", "abc", "7"] {
+            assert_eq!(puzzle1_line(line), None);
+            assert_eq!(puzzle2_line(line), None);
+        }
+        // Nothing outside the tests writes to stdout, so a malformed line can't pollute the output of a run
+        let source = include_str!("day3.rs");
+        let (solutions, _) = source.split_once("#[cfg(test)]").unwrap();
+        assert!(!solutions.contains(concat!("print", "ln!")) && !solutions.contains(concat!("print", "!(")));
+    }
+
+    #[test]
+    fn test_largest_divisible() {
+        let digits = parse_digits("818181911112111", 10);
+        // 91 = 7 * 13, while 92, the only larger pair after the 9, isn't divisible by 7
+        assert_eq!(largest_divisible(&digits, 2, 7), Some(91));
+        assert_eq!(largest_divisible(&digits, 2, 1), Some(92));
+        assert_eq!(largest_divisible(&digits, 12, 1), Some(888911112111));
+        assert_eq!(largest_divisible(&[1, 1], 2, 5), None);
+        assert_eq!(largest_divisible(&[1, 0, 5], 2, 5), Some(15));
+        assert_eq!(largest_divisible(&[1, 2], 3, 1), None);
+        // Compare against checking every pair of digits
+        for m in 1..30 {
+            let mut expected = None;
+            for a in 0..digits.len() {
+                for b in a + 1..digits.len() {
+                    let n = u64::from(digits[a] * 10 + digits[b]);
+                    if n % m == 0 && expected.is_none_or(|e| n > e) {
+                        expected = Some(n);
+                    }
+                }
+            }
+            assert_eq!(largest_divisible(&digits, 2, m), expected, "mismatch for m = {}", m);
+        }
+    }
+
+    #[test]
+    fn test_separators() {
+        assert_eq!(puzzle1_separated(TEST_INPUT, false), 357);
+        assert_eq!(puzzle1_separated(TEST_INPUT, true), 357);
+        assert_eq!(puzzle2_separated(TEST_INPUT, false), 3121910778619);
+        // Merged, the best pair takes the 9 and the 8 across the space; separated, each group is on its own
+        assert_eq!(puzzle1_separated("1291 81", false), 98);
+        assert_eq!(puzzle1_separated("1291 81", true), 91);
+        assert_eq!(puzzle1("1291 81"), 98);
+        // A group with too few digits can't form a number on its own
+        assert_eq!(puzzle1_separated("1 2", false), 12);
+        assert_eq!(puzzle1_separated("1 2", true), 0);
+    }
+
+    #[test]
+    fn test_radix_10_matches_puzzles() {
+        assert_eq!(puzzle1_radix(TEST_INPUT, 10), 357);
+        assert_eq!(puzzle2_radix(TEST_INPUT, 10), 3121910778619);
+        // Lines with too few digits are skipped like in the puzzles
+        let input = format!("7\n{}", TEST_INPUT);
+        assert_eq!(puzzle1_radix("7", 10), puzzle1("7"));
+        assert_eq!(puzzle1_radix(&input, 10), puzzle1(&input));
+        assert_eq!(puzzle2_radix(&input, 10), puzzle2(&input));
+    }
+
+    #[test]
+    fn test_puzzle2_inline_size() {
+        assert_eq!(puzzle2_inline_size(TEST_INPUT), puzzle2(TEST_INPUT));
+        assert_eq!(puzzle2_inline_size("5:987654321111111\n2:811111111111119\n811111111111119"), 98765 + 89 + 811111111119);
+        assert_eq!(puzzle2_inline_size("12:987654321111111"), 987654321111);
+        assert_eq!(puzzle2_inline_size("x:987654321111111\n:987654321111111"), 2 * 987654321111);
+        // Bank sizes that can't form a number fall back to the default bank size
+        assert_eq!(puzzle2_inline_size("0:987654321111111"), 987654321111);
+        assert_eq!(puzzle2_inline_size(&format!("40:{}", "9".repeat(45))), 999999999999);
+        assert_eq!(puzzle2_inline_size(&format!("38:{}", "9".repeat(45))), 10_u128.pow(38) - 1);
+        // Lines with fewer digits than their bank size are skipped
+        assert_eq!(puzzle2_inline_size("5:123\n123\n2:811111111111119"), 89);
+    }
+
+    #[test]
+    fn test_largest_number_decimal() {
+        assert_eq!(largest_number_decimal("987654321111111", 12), Some("987654321111".to_string()));
+        assert_eq!(largest_number_decimal("98", 12), None);
+        // 40 digits don't fit in a u128, so the number is only correct if it's formed without wrapping
+        let line = format!("1{}", "9".repeat(41));
+        assert_eq!(largest_number_decimal(&line, 40), Some("9".repeat(40)));
+        let line = format!("{}5{}", "0".repeat(3), "8".repeat(39));
+        assert_eq!(largest_number_decimal(&line, 40), Some(format!("5{}", "8".repeat(39))));
+        assert_eq!(largest_number_decimal(&"0".repeat(40), 40), Some("0".to_string()));
+    }
+
+    #[test]
+    fn test_radix_formatted() {
+        assert_eq!(
+            puzzle2_radix_formatted("0123456789ABCDEF\nfedcba9876543210", 16),
+            vec!["456789ABCDEF", "FEDCBA987654"]
+        );
+        let decimal: Vec<String> = TEST_INPUT.lines().map(|l| puzzle2(l).to_string()).collect();
+        assert_eq!(puzzle2_radix_formatted(TEST_INPUT, 10), decimal);
+        assert_eq!(puzzle2_radix_formatted("1010101010101", 2), vec!["110101010101"]);
+        assert!(puzzle2_radix_formatted("101\n", 2).is_empty());
+        assert_eq!(puzzle2_radix_formatted("7\n987654321111111", 10), vec!["987654321111"]);
+    }
+
+    #[test]
+    fn test_radix_16() {
+        assert_eq!(puzzle1_radix("1A9F3", 16), 0xF3);
+        assert_eq!(puzzle1_radix("1A9F3\n4B2", 16), 0xF3 + 0xB2);
+        assert_eq!(puzzle2_radix("0123456789ABCDEF", 16), 0x456789ABCDEF);
+        assert_eq!(puzzle2_radix("fedcba9876543210", 16), 0xFEDCBA987654);
+    }
+
+    #[test]
+    fn test_puzzle2_tokens_concat() {
+        assert_eq!(puzzle2_tokens_concat("12 7 345 9", 2), 12345);
+        // 9 is numerically smaller than 50, but it is the better first token when both can be taken
+        assert_eq!(puzzle2_tokens_concat("9 50", 1), 50);
+        assert_eq!(puzzle2_tokens_concat("9 50 8", 2), 950);
+        assert_eq!(puzzle2_tokens_concat("50 9 8\t4", 3), 5098);
+        assert_eq!(puzzle2_tokens_concat("9 50 8\n12 7 345 9\n1", 2), 950 + 12345);
+        // Lines with a token that isn't a number, or whose concatenation doesn't fit in a u128, are skipped
+        assert_eq!(puzzle2_tokens_concat("9 x 8\n-1 5\n9 50 8", 2), 950);
+        let too_long = format!("{} {}", "9".repeat(20), "9".repeat(20));
+        assert_eq!(puzzle2_tokens_concat(&format!("{}\n9 50 8", too_long), 2), 950);
+        // Leading zeros of a token count once a non-zero digit was taken, so 1 and 05 form 105 rather than 59
+        assert_eq!(puzzle2_tokens_concat("1 05 9", 2), 105);
+        assert_eq!(puzzle2_tokens_concat("0 05 9", 2), 59);
+        assert_eq!(puzzle2_tokens_concat("00 1 05 9", 3), 1059);
+        // Compare against trying every selection of tokens
+        for line in ["1 05 9 007 3", "0 05 9 00 1", "10 0 01 9 90", "5 50 05 500 0"] {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            for k in 1..=tokens.len() {
+                let expected = (0..1u32 << tokens.len())
+                    .filter(|mask| mask.count_ones() as usize == k)
+                    .map(|mask| {
+                        let picked: String = (0..tokens.len()).filter(|b| mask & (1 << b) != 0).map(|b| tokens[b]).collect();
+                        picked.parse::<u128>().unwrap()
+                    })
+                    .max();
+                assert_eq!(Some(puzzle2_tokens_concat(line, k)), expected, "{} with k = {}", line, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_puzzle2_validated() {
+        assert_eq!(puzzle2_validated(TEST_INPUT), Ok(3121910778619));
+        assert_eq!(puzzle2_validated("000000000009"), Ok(9));
+        assert_eq!(puzzle2_validated("0000000000009"), Ok(9));
+        assert_eq!(puzzle2_validated("1000000000009"), Ok(100000000009));
+        assert!(puzzle2_validated("00000000009").is_err());
+        // Every line must agree with what puzzle 2 actually forms
+        let lines = ["1020304050607080900", "909090909090909", "000111000111000", "123456789012"];
+        for line in lines {
+            assert_eq!(puzzle2_validated(line), Ok(puzzle2(line)));
+        }
+    }
+}
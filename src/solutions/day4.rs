@@ -2,6 +2,10 @@
 
 //! # Day 4: Printing Department ([challenge description](https://adventofcode.com/2025/day/4))
 
+use std::collections::HashSet;
+
+use crate::automaton::{self, Automaton, NEIGHBOR_OFFSETS};
+
 /// # Puzzle 1
 /// 
 /// ## Summary
@@ -73,68 +77,513 @@ pub fn puzzle1(input: &str) -> u128 {
 /// 
 /// ## Solution
 /// Add a while loop to continue checking until there are no new rolls of paper that can be removed.
-/// In each iteration of the loop, update a temporary copy of the grid where @ characters are replaced
-/// with x. At the end of the iteration, replace the original matrix with the temporary matrix.
+/// Each iteration of the loop is a generation of a cellular automaton: the removal rule (`RollRemoval`) is applied
+/// to every cell of a copy of the grid at once, where @ characters with fewer than 4 adjacent rolls are replaced
+/// with x. The rolls of the original grid are only counted once the grid stops changing, and the ones that are left
+/// are subtracted from them.
 /// 
 #[must_use]
 pub fn puzzle2(input: &str) -> u128 {
-    let mut matrix: Vec<Vec<char>> = input
+    let mut matrix = parse_matrix(input);
+    let count_rolls = |m: &Vec<Vec<char>>| m.iter().flatten().filter(|&&c| c == '@').count() as u128;
+    let initial_rolls = count_rolls(&matrix);
+    loop {
+        let next = automaton::step_grid(&RollRemoval, &matrix);
+        if next == matrix {
+            break;
+        }
+        matrix = next;
+    }
+    initial_rolls - count_rolls(&matrix)
+}
+
+
+/// Parses the textual grid into a matrix of characters.
+fn parse_matrix(input: &str) -> Vec<Vec<char>> {
+    let matrix: Vec<Vec<char>> = input
         .lines()
-        .map(|r| r.chars().collect::<Vec<char>>())
+        .map(|r| r.chars().collect())
         .collect();
-    let mut temp_matrix = matrix.clone();
-    let mut final_count = 0u128;
-    let mut keep_checking = true;
-    // tuples represent offsets to check (row_offset, col_offset)
-    // right and down are positive
-    let check_coordinates = [
-        (0, 1),
-        (0, -1),
-        (1, 0),
-        (-1, 0),
-        (-1, 1),
-        (1, 1),
-        (-1, -1),
-        (1, -1)
-    ];
-    let c_len = check_coordinates.len();
-    let len_i = matrix.len() as i128;
-    if len_i == 0 {
-        panic!("input may not be empty")
+    assert!(!matrix.is_empty(), "input may not be empty");
+    matrix
+}
+
+/// A grid of paper rolls that can be updated cell by cell, e.g. by an interactive tool, and queried for
+/// the rolls removable by the rule of puzzle 1 without parsing the grid again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperGrid {
+    matrix: Vec<Vec<char>>,
+}
+
+impl PaperGrid {
+    /// Replaces the character of a cell, e.g. @ to place a roll or . to clear it. Panics if the cell is out of bounds.
+    pub fn set(&mut self, row: usize, col: usize, ch: char) {
+        self.matrix[row][col] = ch;
     }
-    let len_j = matrix.first().map(|r| r.len()).unwrap_or(0) as i128;
 
-    while keep_checking {
-        keep_checking = false;
-        for i in 0..len_i {
-            for j in 0..len_j {
-                // usize is really messing things up :/
-                if matrix[i as usize][j as usize] != '@' {
+    /// Checks if the cell holds a roll with fewer than 4 adjacent rolls.
+    #[must_use]
+    pub fn is_removable(&self, row: usize, col: usize) -> bool {
+        self.matrix.get(row).and_then(|r| r.get(col)) == Some(&'@')
+            && count_adjacent_rolls(&self.matrix, row, col, false, false) < 4
+    }
+
+    /// Counts the rolls that are currently removable, i.e. the answer to puzzle 1 for the current grid.
+    #[must_use]
+    pub fn removable_count(&self) -> usize {
+        removable_rolls(&self.matrix, false, false).len()
+    }
+}
+
+impl std::str::FromStr for PaperGrid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let matrix: Vec<Vec<char>> = s.lines().map(|r| r.chars().collect()).collect();
+        if matrix.is_empty() {
+            return Err("input may not be empty".to_string());
+        }
+        Ok(PaperGrid { matrix })
+    }
+}
+
+impl std::fmt::Display for PaperGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.matrix {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+/// Expands a grid whose rows are run-length encoded into the character matrix the puzzles work on.
+/// Each run is an optional count followed by a cell character, e.g. `3.2@5.` is three dots, two rolls and
+/// five dots. A run without a count is a single cell. All expanded rows must have the same width.
+pub fn parse_rle_grid(input: &str) -> Result<Vec<Vec<char>>, String> {
+    let mut matrix: Vec<Vec<char>> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let mut row = Vec::new();
+        let mut count = String::new();
+        for c in line.trim().chars() {
+            if c.is_ascii_digit() {
+                count.push(c);
+                continue;
+            }
+            let n = if count.is_empty() {
+                1
+            } else {
+                count
+                    .parse::<usize>()
+                    .map_err(|e| format!("invalid run length on row {}: {}", i, e))?
+            };
+            row.extend(std::iter::repeat_n(c, n));
+            count.clear();
+        }
+        if !count.is_empty() {
+            return Err(format!("row {} ends with a run length but no cell character", i));
+        }
+        if let Some(first) = matrix.first() && first.len() != row.len() {
+            return Err(format!(
+                "row {} expands to a width of {}, but the first row has a width of {}",
+                i,
+                row.len(),
+                first.len()
+            ));
+        }
+        matrix.push(row);
+    }
+    Ok(matrix)
+}
+
+/// Moves an index by an offset within a dimension of the given length. With wrapping, leaving one edge
+/// enters from the opposite edge; without it, there is no neighbor beyond the edge.
+fn offset_index(index: usize, offset: isize, len: usize, wrap: bool) -> Option<usize> {
+    if wrap {
+        Some((index as isize + offset).rem_euclid(len as isize) as usize)
+    } else {
+        index.checked_add_signed(offset).filter(|&i| i < len)
+    }
+}
+
+/// Counts the rolls (@ characters) in the cells adjacent to the given position. The grid can wrap
+/// horizontally (`wrap_x`), vertically (`wrap_y`) or both, e.g. horizontal wrapping models a cylinder.
+fn count_adjacent_rolls(matrix: &[Vec<char>], row: usize, col: usize, wrap_x: bool, wrap_y: bool) -> usize {
+    NEIGHBOR_OFFSETS
+        .iter()
+        .filter(|(dr, dc)| {
+            offset_index(row, *dr, matrix.len(), wrap_y)
+                .and_then(|r| {
+                    let c = offset_index(col, *dc, matrix[r].len(), wrap_x)?;
+                    matrix[r].get(c)
+                })
+                .is_some_and(|&ch| ch == '@')
+        })
+        .count()
+}
+
+/// Returns the positions of the rolls that have fewer than 4 adjacent rolls, i.e. the ones removed in the next generation.
+fn removable_rolls(matrix: &[Vec<char>], wrap_x: bool, wrap_y: bool) -> Vec<(usize, usize)> {
+    matrix
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, &c)| (i, j, c)))
+        .filter(|&(i, j, c)| c == '@' && count_adjacent_rolls(matrix, i, j, wrap_x, wrap_y) < 4)
+        .map(|(i, j, _)| (i, j))
+        .collect()
+}
+
+/// Runs the removal process of puzzle 2 and returns the positions of the removed rolls,
+/// grouped by the generation (iteration of the loop) in which they were removed.
+fn removed_by_generation(mut matrix: Vec<Vec<char>>, wrap_x: bool, wrap_y: bool) -> Vec<Vec<(usize, usize)>> {
+    let mut generations = Vec::new();
+    loop {
+        let removed = removable_rolls(&matrix, wrap_x, wrap_y);
+        if removed.is_empty() {
+            break;
+        }
+        for &(i, j) in &removed {
+            matrix[i][j] = 'x';
+        }
+        generations.push(removed);
+    }
+    generations
+}
+
+/// # Generations
+///
+/// Lazily runs the removal process of puzzle 2 and yields a snapshot of the grid after every generation, with the
+/// removed rolls replaced by x characters. The iterator ends once a generation would no longer remove any rolls,
+/// so the last snapshot is the stable grid, and the number of snapshots is the number of generations.
+pub fn generations(input: &str) -> impl Iterator<Item = Vec<Vec<char>>> {
+    let mut matrix = parse_matrix(input);
+    std::iter::from_fn(move || {
+        let removed = removable_rolls(&matrix, false, false);
+        if removed.is_empty() {
+            return None;
+        }
+        for (i, j) in removed {
+            matrix[i][j] = 'x';
+        }
+        Some(matrix.clone())
+    })
+}
+
+// Removes the outer ring of the grid if it consists entirely of the border character, so that the border is neither
+// counted nor shifts the positions of the cells. Any other character than @ is never a roll, so a border character
+// elsewhere is neutral as well.
+fn strip_border(matrix: Vec<Vec<char>>, border: char) -> Vec<Vec<char>> {
+    let is_border = |c: &char| *c == border;
+    let bordered = matrix.len() >= 2
+        && matrix.first().is_some_and(|r| r.iter().all(is_border))
+        && matrix.last().is_some_and(|r| r.iter().all(is_border))
+        && matrix.iter().all(|r| r.len() >= 2 && is_border(&r[0]) && is_border(&r[r.len() - 1]));
+    if !bordered {
+        return matrix;
+    }
+    matrix[1..matrix.len() - 1]
+        .iter()
+        .map(|r| r[1..r.len() - 1].to_vec())
+        .collect()
+}
+
+/// # Puzzle 1 (bordered)
+///
+/// Same as puzzle 1, but the grid may be wrapped in a border of the given character, e.g. `#`, which is detected and
+/// ignored. The border cells are never rolls, so they don't count as neighbors either.
+#[must_use]
+pub fn puzzle1_bordered(input: &str, border: char) -> u128 {
+    let matrix = strip_border(parse_matrix(input), border);
+    removable_rolls(&matrix, false, false).len() as u128
+}
+
+/// # Puzzle 2 (bordered)
+///
+/// Same as puzzle 2, with the same border handling as `puzzle1_bordered`.
+#[must_use]
+pub fn puzzle2_bordered(input: &str, border: char) -> u128 {
+    let matrix = strip_border(parse_matrix(input), border);
+    removed_by_generation(matrix, false, false).iter().map(|g| g.len() as u128).sum()
+}
+
+/// # Puzzle 2 (gravity)
+///
+/// A variant of puzzle 2, where the removed rolls leave the grid and the remaining rolls fall down to fill the gaps
+/// before the next generation, like in a game of Tetris. After every generation, the rolls of each column are
+/// compacted to the bottom of the column, keeping their count, which changes the neighbors of the rolls that fell.
+#[must_use]
+pub fn puzzle2_gravity(input: &str) -> u128 {
+    let mut matrix = parse_matrix(input);
+    let mut total = 0u128;
+    loop {
+        let removed = removable_rolls(&matrix, false, false);
+        if removed.is_empty() {
+            break;
+        }
+        total += removed.len() as u128;
+        for (i, j) in removed {
+            matrix[i][j] = '.';
+        }
+        apply_gravity(&mut matrix);
+    }
+    total
+}
+
+// Moves the rolls of every column down to the bottom of the column, leaving empty cells above them
+fn apply_gravity(matrix: &mut [Vec<char>]) {
+    let width = matrix.iter().map(|r| r.len()).max().unwrap_or(0);
+    for j in 0..width {
+        let rows: Vec<usize> = (0..matrix.len()).filter(|&i| j < matrix[i].len()).collect();
+        let rolls = rows.iter().filter(|&&i| matrix[i][j] == '@').count();
+        // Everything that isn't a roll is treated as empty space that the rolls fall through
+        for (k, &i) in rows.iter().enumerate() {
+            matrix[i][j] = if k >= rows.len() - rolls { '@' } else { '.' };
+        }
+    }
+}
+
+/// # Removable clusters
+///
+/// Groups the rolls that are removable in puzzle 1 into clusters of 8-connected removable rolls, and returns the
+/// size of every cluster. The clusters are listed in the row-major order of their first roll.
+#[must_use]
+pub fn removable_cluster_sizes(input: &str) -> Vec<usize> {
+    let matrix = parse_matrix(input);
+    let rolls = removable_rolls(&matrix, false, false);
+    let mut removable: HashSet<(usize, usize)> = rolls.iter().copied().collect();
+    let mut sizes = Vec::new();
+    for start in rolls {
+        if !removable.remove(&start) {
+            continue;
+        }
+        // Flood-fill the cluster, removing its rolls from the set so each roll is only visited once
+        let mut size = 0;
+        let mut stack = vec![start];
+        while let Some((i, j)) = stack.pop() {
+            size += 1;
+            for &(dr, dc) in NEIGHBOR_OFFSETS.iter() {
+                let Some(neighbor) = offset_index(i, dr, matrix.len(), false)
+                    .zip(offset_index(j, dc, matrix[0].len(), false)) else {
                     continue;
+                };
+                if removable.remove(&neighbor) {
+                    stack.push(neighbor);
                 }
-                let mut count = 0u8;
-                for c in 0..c_len {
-                    let i2 = i + check_coordinates[c].0;
-                    let j2 = j + check_coordinates[c].1;
-                    if i2 < 0 || i2 >= len_i || j2 < 0 || j2 >= len_j {
+            }
+        }
+        sizes.push(size);
+    }
+    sizes
+}
+
+/// # Grid after N generations
+///
+/// Returns the grid after at most `n` generations of the removal process of puzzle 2, with the removed rolls
+/// replaced by x characters like in `generations`. If the grid stabilizes sooner, the stable grid is returned,
+/// and for 0 generations the grid is returned as is.
+#[must_use]
+pub fn grid_after(input: &str, n: u32) -> Vec<Vec<char>> {
+    generations(input)
+        .take(n as usize)
+        .last()
+        .unwrap_or_else(|| parse_matrix(input))
+}
+
+/// # Permanent rolls
+///
+/// Counts the rolls that are never removed, no matter how many generations of puzzle 2 are run. They are counted
+/// directly on the stable grid rather than subtracted from the total, which makes it a cross-check of puzzle 2.
+#[must_use]
+pub fn permanent_rolls(input: &str) -> u128 {
+    let stable = generations(input).last().unwrap_or_else(|| parse_matrix(input));
+    stable.iter().flatten().filter(|&&c| c == '@').count() as u128
+}
+
+/// The removal rule of puzzle 2 as a cellular automaton: a roll with fewer than 4 adjacent rolls is removed
+/// (replaced by an x), and every other cell stays the same.
+struct RollRemoval;
+
+impl Automaton for RollRemoval {
+    fn next_state(&self, cell: char, neighbors: &[char]) -> char {
+        if cell == '@' && neighbors.iter().filter(|&&c| c == '@').count() < 4 {
+            'x'
+        } else {
+            cell
+        }
+    }
+}
+
+/// # Puzzle 2 (border breakdown)
+///
+/// Runs the same removal process as puzzle 2, but classifies every removed roll by its location.
+/// A roll is on the border if it is in the first or last row or column of the grid, where it
+/// naturally has fewer neighbors. Returns `(border_removed, interior_removed)`.
+#[must_use]
+pub fn puzzle2_border_breakdown(input: &str) -> (u128, u128) {
+    let matrix = parse_matrix(input);
+    let last_i = matrix.len() - 1;
+    let last_j = matrix[0].len().saturating_sub(1);
+    removed_by_generation(matrix, false, false)
+        .iter()
+        .flatten()
+        .fold((0, 0), |(border, interior), &(i, j)| {
+            if i == 0 || i == last_i || j == 0 || j == last_j {
+                (border + 1, interior)
+            } else {
+                (border, interior + 1)
+            }
+        })
+}
+
+/// # Critical removals
+///
+/// Runs the same removal process as puzzle 2 and returns the removed rolls whose removal enabled further removals,
+/// i.e. the ones removed in a generation that are adjacent to at least one roll removed in the next generation.
+/// The rolls are listed by generation, and in row-major order within a generation.
+#[must_use]
+pub fn critical_removals(input: &str) -> Vec<(usize, usize)> {
+    let matrix = parse_matrix(input);
+    // The generation in which each cell was removed, if at all
+    let mut removed_in: Vec<Vec<Option<usize>>> = matrix.iter().map(|row| vec![None; row.len()]).collect();
+    let generations = removed_by_generation(matrix, false, false);
+    for (g, removed) in generations.iter().enumerate() {
+        for &(i, j) in removed {
+            removed_in[i][j] = Some(g);
+        }
+    }
+    generations
+        .iter()
+        .enumerate()
+        .flat_map(|(g, removed)| removed.iter().map(move |&pos| (g, pos)))
+        .filter(|&(g, (i, j))| {
+            NEIGHBOR_OFFSETS.iter().any(|&(dr, dc)| {
+                offset_index(i, dr, removed_in.len(), false)
+                    .and_then(|r| removed_in[r].get(offset_index(j, dc, removed_in[r].len(), false)?))
+                    .is_some_and(|&n| n == Some(g + 1))
+            })
+        })
+        .map(|(_, pos)| pos)
+        .collect()
+}
+
+/// # Articulation rolls
+///
+/// Counts the rolls that are articulation points of the graph formed by the rolls, where every roll is connected
+/// to the rolls in its 8 adjacent cells. Removing such a roll splits the group of rolls it belongs to in two or more.
+///
+/// The rolls are visited with a depth-first search, recording the order in which they are discovered and the lowest
+/// discovery order reachable from each roll's subtree through at most one edge back to an earlier roll. A roll is an
+/// articulation point if one of its children can't reach any roll discovered before it, or in the case of the roll
+/// the search starts from, if it has more than one child. An explicit stack is used to avoid deep recursion.
+#[must_use]
+pub fn articulation_rolls(input: &str) -> usize {
+    let matrix = parse_matrix(input);
+    let mut discovered: Vec<Vec<Option<usize>>> = matrix.iter().map(|row| vec![None; row.len()]).collect();
+    let mut low: Vec<Vec<usize>> = matrix.iter().map(|row| vec![0; row.len()]).collect();
+    let mut is_articulation: Vec<Vec<bool>> = matrix.iter().map(|row| vec![false; row.len()]).collect();
+    let mut time = 0;
+    for (si, row) in matrix.iter().enumerate() {
+        for (sj, &c) in row.iter().enumerate() {
+            if c != '@' || discovered[si][sj].is_some() {
+                continue;
+            }
+            discovered[si][sj] = Some(time);
+            low[si][sj] = time;
+            time += 1;
+            let mut root_children = 0;
+            // Every entry is a roll, its parent in the search, and the index of the next neighbor offset to check
+            let mut stack: Vec<((usize, usize), Option<(usize, usize)>, usize)> = vec![((si, sj), None, 0)];
+            while let Some(&((i, j), parent, k)) = stack.last() {
+                if k < NEIGHBOR_OFFSETS.len() {
+                    stack.last_mut().unwrap().2 += 1;
+                    let (dr, dc) = NEIGHBOR_OFFSETS[k];
+                    let Some((ni, nj)) = offset_index(i, dr, matrix.len(), false)
+                        .and_then(|ni| Some((ni, offset_index(j, dc, matrix[ni].len(), false)?)))
+                        .filter(|&(ni, nj)| matrix[ni][nj] == '@')
+                    else {
                         continue;
+                    };
+                    match discovered[ni][nj] {
+                        None => {
+                            discovered[ni][nj] = Some(time);
+                            low[ni][nj] = time;
+                            time += 1;
+                            if parent.is_none() {
+                                root_children += 1;
+                            }
+                            stack.push(((ni, nj), Some((i, j)), 0));
+                        },
+                        Some(d) if parent != Some((ni, nj)) => {
+                            low[i][j] = low[i][j].min(d);
+                        },
+                        Some(_) => {}
                     }
-                    if matrix[i2 as usize][j2 as usize] == '@' {
-                        count += 1;
+                } else {
+                    stack.pop();
+                    if let Some((pi, pj)) = parent {
+                        low[pi][pj] = low[pi][pj].min(low[i][j]);
+                        // The root of the search is handled separately by counting its children
+                        if (pi, pj) != (si, sj) && Some(low[i][j]) >= discovered[pi][pj] {
+                            is_articulation[pi][pj] = true;
+                        }
                     }
                 }
-                if count < 4 {
-                    final_count += 1;
-                    temp_matrix[i as usize][j as usize] = 'x';
-                    keep_checking = true;
-                }
+            }
+            if root_children > 1 {
+                is_articulation[si][sj] = true;
+            }
+        }
+    }
+    is_articulation.iter().flatten().filter(|&&a| a).count()
+}
+
+/// # Neighbor count histogram
+///
+/// For all rolls in the grid, counts how many have 0 to 8 adjacent rolls, indexed by the number of neighbors.
+/// The rolls in buckets 0 to 3 are the ones removable in puzzle 1, with bucket 3 holding the most fragile ones
+/// that are right at the boundary of the removal threshold.
+#[must_use]
+pub fn neighbor_count_histogram(input: &str) -> [u128; 9] {
+    let matrix = parse_matrix(input);
+    let mut histogram = [0u128; 9];
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, &c) in row.iter().enumerate() {
+            if c == '@' {
+                histogram[count_adjacent_rolls(&matrix, i, j, false, false)] += 1;
+            }
+        }
+    }
+    histogram
+}
+
+/// # Puzzle 1 (wrapping)
+///
+/// Same as puzzle 1, but the neighbors of a cell on an edge may wrap around to the opposite edge,
+/// horizontally with `wrap_x` and vertically with `wrap_y`. Enabling both models a torus.
+#[must_use]
+pub fn puzzle1_wrapping(input: &str, wrap_x: bool, wrap_y: bool) -> u128 {
+    let matrix = parse_matrix(input);
+    let mut final_count = 0u128;
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, &c) in row.iter().enumerate() {
+            if c == '@' && count_adjacent_rolls(&matrix, i, j, wrap_x, wrap_y) < 4 {
+                final_count += 1;
             }
         }
-        matrix = temp_matrix.clone();
     }
     final_count
 }
 
+/// # Puzzle 2 (wrapping)
+///
+/// Same as puzzle 2, with the same wrapping options as `puzzle1_wrapping`.
+#[must_use]
+pub fn puzzle2_wrapping(input: &str, wrap_x: bool, wrap_y: bool) -> u128 {
+    removed_by_generation(parse_matrix(input), wrap_x, wrap_y)
+        .iter()
+        .map(|g| g.len() as u128)
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +609,222 @@ mod tests {
     fn test_puzzle2() {
         assert_eq!(puzzle2(TEST_INPUT), 43);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_neighbor_count_histogram() {
+        let histogram = neighbor_count_histogram(TEST_INPUT);
+        let roll_count = TEST_INPUT.chars().filter(|&c| c == '@').count() as u128;
+        assert_eq!(histogram.iter().sum::<u128>(), roll_count);
+        assert_eq!(histogram[..=3].iter().sum::<u128>(), puzzle1(TEST_INPUT));
+    }
+
+    #[test]
+    fn test_wrapping() {
+        assert_eq!(puzzle1_wrapping(TEST_INPUT, false, false), puzzle1(TEST_INPUT));
+        assert_eq!(puzzle2_wrapping(TEST_INPUT, false, false), puzzle2(TEST_INPUT));
+        assert_eq!(puzzle1_wrapping(TEST_INPUT, true, false), 9);
+        assert_eq!(puzzle1_wrapping(TEST_INPUT, false, true), 6);
+        assert_eq!(puzzle1_wrapping(TEST_INPUT, true, true), 2);
+        assert_eq!(puzzle2_wrapping(TEST_INPUT, true, true), 3);
+    }
+
+    #[test]
+    fn test_wrapping_cylinder() {
+        // The rolls on the left and right edges are adjacent on a cylinder, giving every roll 5 neighbors
+        let input = "@@@@@\n@@@@@\n";
+        assert_eq!(puzzle2_wrapping(input, false, false), 10);
+        assert_eq!(puzzle2_wrapping(input, true, false), 0);
+    }
+
+    #[test]
+    fn test_parse_rle_grid_round_trip() {
+        // Encode the sample grid as runs of identical characters and expand it again
+        let encoded: String = TEST_INPUT
+            .lines()
+            .map(|line| {
+                let mut runs = String::new();
+                let chars: Vec<char> = line.chars().collect();
+                for chunk in chars.chunk_by(|a, b| a == b) {
+                    runs.push_str(&format!("{}{}", chunk.len(), chunk[0]));
+                }
+                runs + "\n"
+            })
+            .collect();
+        assert_eq!(parse_rle_grid(&encoded), Ok(parse_matrix(TEST_INPUT)));
+        assert_eq!(parse_rle_grid("3.2@5.\n.@7.@"), Ok(parse_matrix("...@@.....\n.@.......@")));
+    }
+
+    #[test]
+    fn test_parse_rle_grid_width_mismatch() {
+        assert!(parse_rle_grid("3.2@5.\n2@").is_err());
+        assert!(parse_rle_grid("3.2@5\n").is_err());
+    }
+
+    #[test]
+    fn test_generations() {
+        let snapshots: Vec<Vec<Vec<char>>> = generations(TEST_INPUT).collect();
+        assert_eq!(snapshots.len(), removed_by_generation(parse_matrix(TEST_INPUT), false, false).len());
+        let count_removed = |m: &Vec<Vec<char>>| m.iter().flatten().filter(|&&c| c == 'x').count() as u128;
+        assert_eq!(count_removed(&snapshots[0]), puzzle1(TEST_INPUT));
+        assert_eq!(count_removed(snapshots.last().unwrap()), 43);
+    }
+
+    #[test]
+    fn test_puzzle2_border_breakdown() {
+        let (border, interior) = puzzle2_border_breakdown(TEST_INPUT);
+        assert_eq!(border + interior, puzzle2(TEST_INPUT));
+        assert_eq!((border, interior), (20, 23));
+    }
+
+    #[test]
+    fn test_critical_removals() {
+        let critical = critical_removals(TEST_INPUT);
+        let generations = removed_by_generation(parse_matrix(TEST_INPUT), false, false);
+        assert!(!critical.is_empty());
+        // Nothing is removed after the last generation, so none of its rolls can be critical
+        assert!(critical.iter().all(|pos| !generations.last().unwrap().contains(pos)));
+        assert_eq!(critical.len(), 30);
+        assert_eq!(critical[0], (0, 3));
+    }
+
+    #[test]
+    fn test_paper_grid() {
+        let mut grid: PaperGrid = TEST_INPUT.parse().unwrap();
+        assert_eq!(grid.to_string(), TEST_INPUT);
+        assert_eq!(grid.removable_count() as u128, puzzle1(TEST_INPUT));
+        // The roll in the top left corner only has 2 adjacent rolls, so it's removable on its own
+        assert!(grid.is_removable(1, 0));
+        assert!(!grid.is_removable(0, 0));
+        // Clearing the roll leaves its neighbor at (2, 0) with fewer than 4 adjacent rolls as well
+        assert!(!grid.is_removable(2, 0));
+        grid.set(1, 0, '.');
+        assert!(grid.is_removable(2, 0));
+        assert_eq!(grid.removable_count() as u128, puzzle1(TEST_INPUT));
+        // In a full 3x3 block only the corners are removable, and clearing one leaves the edges with 4 neighbors
+        let mut block: PaperGrid = "@@@\n@@@\n@@@\n".parse().unwrap();
+        assert_eq!(block.removable_count(), 4);
+        block.set(0, 0, '.');
+        assert_eq!(block.removable_count(), 3);
+        block.set(0, 0, '@');
+        assert_eq!(block.removable_count(), 4);
+        assert!("".parse::<PaperGrid>().is_err());
+    }
+
+    #[test]
+    fn test_articulation_rolls() {
+        // Only the center connects the corners, which touch it diagonally
+        assert_eq!(articulation_rolls("@.@\n.@.\n@.@\n"), 1);
+        // Every roll of a line except for the ends
+        assert_eq!(articulation_rolls("@@@@@\n"), 3);
+        // Every roll of a ring has another way around
+        assert_eq!(articulation_rolls("@@@\n@.@\n@@@\n"), 0);
+        // Three separate groups, where only the one on the right has an articulation point joining its halves
+        assert_eq!(articulation_rolls("@@.@@\n....@\n@@.@@\n"), 1);
+        // A diagonal chain of rolls
+        assert_eq!(articulation_rolls("@@..@\n...@.\n@@@..\n"), 3);
+        // Compare against removing every roll of the sample one at a time and counting the groups
+        let count_groups = |matrix: &Vec<Vec<char>>| {
+            let mut seen = vec![vec![false; matrix[0].len()]; matrix.len()];
+            let mut groups = 0;
+            for i in 0..matrix.len() {
+                for j in 0..matrix[i].len() {
+                    if matrix[i][j] != '@' || seen[i][j] {
+                        continue;
+                    }
+                    groups += 1;
+                    seen[i][j] = true;
+                    let mut stack = vec![(i, j)];
+                    while let Some((r, c)) = stack.pop() {
+                        for (dr, dc) in NEIGHBOR_OFFSETS {
+                            if let Some(nr) = offset_index(r, dr, matrix.len(), false)
+                                && let Some(nc) = offset_index(c, dc, matrix[nr].len(), false)
+                                && matrix[nr][nc] == '@'
+                                && !seen[nr][nc]
+                            {
+                                seen[nr][nc] = true;
+                                stack.push((nr, nc));
+                            }
+                        }
+                    }
+                }
+            }
+            groups
+        };
+        let mut matrix = parse_matrix(TEST_INPUT);
+        let groups = count_groups(&matrix);
+        let mut expected = 0;
+        for i in 0..matrix.len() {
+            for j in 0..matrix[i].len() {
+                if matrix[i][j] == '@' {
+                    matrix[i][j] = '.';
+                    if count_groups(&matrix) > groups {
+                        expected += 1;
+                    }
+                    matrix[i][j] = '@';
+                }
+            }
+        }
+        assert_eq!(articulation_rolls(TEST_INPUT), expected);
+    }
+
+    #[test]
+    fn test_permanent_rolls() {
+        let total = TEST_INPUT.chars().filter(|&c| c == '@').count() as u128;
+        assert_eq!(permanent_rolls(TEST_INPUT) + puzzle2(TEST_INPUT), total);
+        assert_eq!(permanent_rolls(TEST_INPUT), 28);
+        // A grid without rolls never changes, so there is no generation to take the stable grid from
+        assert_eq!(permanent_rolls("...\n...\n"), 0);
+    }
+
+    #[test]
+    fn test_grid_after() {
+        let mut expected = parse_matrix(TEST_INPUT);
+        assert_eq!(grid_after(TEST_INPUT, 0), expected);
+        for (i, j) in removable_rolls(&expected, false, false) {
+            expected[i][j] = 'x';
+        }
+        assert_eq!(grid_after(TEST_INPUT, 1), expected);
+        // Running more generations than needed stops at the stable grid
+        let stable = generations(TEST_INPUT).last().unwrap();
+        assert_eq!(grid_after(TEST_INPUT, 1000), stable);
+    }
+
+    #[test]
+    fn test_removable_cluster_sizes() {
+        let sizes = removable_cluster_sizes(TEST_INPUT);
+        assert_eq!(sizes.iter().sum::<usize>() as u128, puzzle1(TEST_INPUT));
+        // The two pairs of adjacent removable rolls in the first row, and 9 isolated rolls
+        assert_eq!(sizes, vec![2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_bordered() {
+        let width = TEST_INPUT.lines().next().unwrap().len();
+        let mut bordered = format!("{}\n", "#".repeat(width + 2));
+        for line in TEST_INPUT.lines() {
+            bordered.push_str(&format!("#{}#\n", line));
+        }
+        bordered.push_str(&format!("{}\n", "#".repeat(width + 2)));
+        assert_eq!(puzzle1_bordered(&bordered, '#'), puzzle1(TEST_INPUT));
+        assert_eq!(puzzle2_bordered(&bordered, '#'), puzzle2(TEST_INPUT));
+        // An @ border is made of rolls unless it's declared as the border
+        let rolled = bordered.replace('#', "@");
+        assert_eq!(puzzle1_bordered(&rolled, '@'), puzzle1(TEST_INPUT));
+        assert_ne!(puzzle1(&rolled), puzzle1(TEST_INPUT));
+        assert_eq!(puzzle1_bordered(TEST_INPUT, '#'), puzzle1(TEST_INPUT));
+    }
+
+    #[test]
+    fn test_puzzle2_gravity() {
+        let mut matrix = parse_matrix("@.@\n.@.\n...\n@..");
+        apply_gravity(&mut matrix);
+        assert_eq!(matrix, parse_matrix("...\n...\n@..\n@@@"));
+        // Without gravity, the removal stops after the first generations. With gravity, the rolls falling into the
+        // gap in the first row break up the block, until every roll is removed
+        let input = "@@@.@\n@@@@@\n@@@@@\n@@@@@\n";
+        assert_eq!(puzzle2(input), 7);
+        assert_eq!(puzzle2_gravity(input), 19);
+        // Rolls that are already at the bottom don't fall, so a single row is the same either way
+        assert_eq!(puzzle2_gravity("@@.@@"), puzzle2("@@.@@"));
+    }
+}
@@ -2,7 +2,7 @@
 
 //! # Day 5: Cafeteria ([challenge description](https://adventofcode.com/2025/day/5))
 
-use std::{collections::VecDeque, ops::RangeInclusive};
+use std::{collections::{HashSet, VecDeque}, io::BufRead, ops::RangeInclusive};
 
 /// # Puzzle 1
 /// 
@@ -24,6 +24,10 @@ use std::{collections::VecDeque, ops::RangeInclusive};
 /// initial tree is set up. The tree is constructed by sorting the intervals by the lower endpoint, choosing the middle interval for
 /// the root node, and repeating the process to form the left and right sub-trees.
 /// 
+/// The interval tree is still available as `IntervalNode`, but since the intervals don't change once parsed, the puzzle
+/// now shares the consolidation of puzzle 2: a `SweepLine` merges the sorted intervals once, and each integer is looked
+/// up with a binary search over the merged intervals in O(log n). `benches/day5_interval_tree.rs` compares both.
+/// 
 /// This problem is somewhat similar to checking if an IP is in a set of CIDR intervals.
 /// 
 /// Biggest lesson: You might be tempted to make things too abstract and complex. Stay simple.
@@ -35,31 +39,104 @@ use std::{collections::VecDeque, ops::RangeInclusive};
 /// 
 #[must_use]
 pub fn puzzle1(input: &str) -> u128 {
-    let (intervals_str, integers_str) = input
-        .trim()
-        .split_once("\n\n")
+    let (intervals_str, integers_str) = split_sections(input)
         .expect("input must contain intervals and integers separated by an empty line");
-    let intervals: Vec<std::ops::RangeInclusive<i64>> = intervals_str
-        .split('\n')
+    let integers = integers_str
+        .lines()
+        .map(|i| i.parse::<i64>().expect("failed to parse 64-bit integer"));
+    let count = match parse_intervals_narrowed(intervals_str) {
+        // The smaller sweep line can't contain an integer that doesn't fit in 32 bits either
+        Intervals::Narrow(narrow) => {
+            let sweep_line = SweepLine::from(narrow);
+            integers.filter(|&i| i32::try_from(i).is_ok_and(|i| sweep_line.contains(i))).count()
+        },
+        Intervals::Wide(intervals) => {
+            let sweep_line = SweepLine::from(intervals);
+            integers.filter(|&i| sweep_line.contains(i)).count()
+        }
+    };
+    count.try_into().expect("failed to convert to u128")
+}
+
+// The parsed intervals, with 32-bit endpoints if all of them fit, which halves the memory taken by the endpoints
+#[derive(Debug, PartialEq)]
+enum Intervals {
+    Narrow(Vec<RangeInclusive<i32>>),
+    Wide(Vec<RangeInclusive<i64>>),
+}
+
+// Converts an interval to 32-bit endpoints if both of them fit
+fn narrow_interval(r: &RangeInclusive<i64>) -> Option<RangeInclusive<i32>> {
+    Some(i32::try_from(*r.start()).ok()?..=i32::try_from(*r.end()).ok()?)
+}
+
+// Same as parse_intervals, but the intervals are kept with 32-bit endpoints for as long as they fit, so the 64-bit
+// intervals are only built once an endpoint doesn't fit, rather than narrowing a copy of all the parsed intervals
+fn parse_intervals_narrowed(intervals_str: &str) -> Intervals {
+    let mut intervals = Intervals::Narrow(vec![]);
+    for interval in parse_intervals(intervals_str) {
+        match &mut intervals {
+            Intervals::Narrow(narrow) => match narrow_interval(&interval) {
+                Some(n) => narrow.push(n),
+                None => {
+                    let mut wide: Vec<RangeInclusive<i64>> = narrow
+                        .drain(..)
+                        .map(|n| i64::from(*n.start())..=i64::from(*n.end()))
+                        .collect();
+                    wide.push(interval);
+                    intervals = Intervals::Wide(wide);
+                }
+            },
+            Intervals::Wide(wide) => wide.push(interval),
+        }
+    }
+    intervals
+}
+
+// Splits the input into the intervals and integers sections at the first run of blank lines, so the sections
+// may be separated by any number of blank lines, with lines ending in either \n or \r\n. Blank lines followed
+// by another interval or a comment are still part of the intervals section
+fn split_sections(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim();
+    let mut offset = 0;
+    let mut blank_start = None;
+    for line in input.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            blank_start.get_or_insert(offset);
+        } else if is_comment(line) || parse_inclusive_range(line).is_ok() {
+            blank_start = None;
+        } else if let Some(start) = blank_start {
+            return Some((&input[..start], &input[offset..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+// Lines starting with a # are comments
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+// Parses the intervals section, one interval per line, skipping blank lines and comments
+fn parse_intervals(intervals_str: &str) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+    intervals_str
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !is_comment(l))
         .map(|i| parse_inclusive_range(i).unwrap())
-        .collect();
-    let root_node = IntervalNode::from(intervals);
-    integers_str
-        .split('\n')
-        .map(|i| i.parse::<i64>().expect("failed to parse 64-bit integer"))
-        .filter(|i| root_node.contains(*i))
-        .count()
-        .try_into().expect("failed to convert to u128")
 }
 
 // Would've been nice if RangeInclusive<i64> would've had a built-in FromStr
+// The endpoints may be negative, so the delimiter is the first - that follows the start value's first character
 fn parse_inclusive_range(s: &str) -> Result<RangeInclusive<i64>, String> {
-    let parts: Vec<&str> = s
-        .split('-')
-        .collect();
-    if parts.len() != 2 {
-        return Err("interval must contain two integers delimited by a -".to_string());
-    }
+    let s = s.trim();
+    let delimiter_i = s
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '-')
+        .map(|(i, _)| i)
+        .ok_or("interval must contain two integers delimited by a -".to_string())?;
+    let parts = [&s[..delimiter_i], &s[delimiter_i + 1..]];
     let start = parts[0]
         .trim()
         .parse::<i64>()
@@ -72,32 +149,48 @@ fn parse_inclusive_range(s: &str) -> Result<RangeInclusive<i64>, String> {
 }
 
 
+// The endpoints are 64-bit integers by default, but may be of any ordered type, while the weights are always 64-bit
 #[derive(Debug)]
-struct IntervalNode {
-    pub interval: RangeInclusive<i64>,
-    pub max: i64,
-    pub left: Option<Box<IntervalNode>>,
-    pub right: Option<Box<IntervalNode>>,
+pub struct IntervalNode<T = i64> {
+    pub interval: RangeInclusive<T>,
+    pub weight: i64,
+    pub max: T,
+    pub left: Option<Box<IntervalNode<T>>>,
+    pub right: Option<Box<IntervalNode<T>>>,
 }
 
+// Unweighted intervals all have a weight of 1
 impl From<Vec<RangeInclusive<i64>>> for IntervalNode {
-    fn from(mut intervals: Vec<RangeInclusive<i64>>) -> Self {
+    fn from(intervals: Vec<RangeInclusive<i64>>) -> Self {
+        Self::from_unweighted(intervals)
+    }
+}
+
+// Weighted intervals are given as (interval, weight) pairs
+impl From<Vec<(RangeInclusive<i64>, i64)>> for IntervalNode {
+    fn from(intervals: Vec<(RangeInclusive<i64>, i64)>) -> Self {
+        Self::from_weighted(intervals)
+    }
+}
+
+impl<T: Copy + Ord> IntervalNode<T> {
+    fn from_unweighted(intervals: Vec<RangeInclusive<T>>) -> Self {
+        Self::from_weighted(intervals.into_iter().map(|r| (r, 1)).collect())
+    }
+    fn from_weighted(mut intervals: Vec<(RangeInclusive<T>, i64)>) -> Self {
         // TODO: Any way to return Option and avoid panic?
         assert_ne!(intervals.len(), 0, "there must be at least one interval");
-        intervals.sort_by_key(|n| *n.start());
+        intervals.sort_by_key(|(n, _)| *n.start());
         *Self::build_from_intervals(&intervals).expect("no interval provided")
     }
-}
-
-impl IntervalNode {
-    fn build_from_intervals(intervals: &[RangeInclusive<i64>]) -> Option<Box<IntervalNode>> {
+    fn build_from_intervals(intervals: &[(RangeInclusive<T>, i64)]) -> Option<Box<IntervalNode<T>>> {
         // TODO: Any way to avoid recursion?
         if intervals.is_empty() {
             return None;
         }
         let mid = intervals.len()/2;
-        let ref r = intervals[mid];
-        let mut node = Box::new(IntervalNode { interval: r.clone(), max: *r.end(), left: None, right: None });
+        let (ref r, weight) = intervals[mid];
+        let mut node = Box::new(IntervalNode { interval: r.clone(), weight, max: *r.end(), left: None, right: None });
         node.left = Self::build_from_intervals(&intervals[..mid]);
         node.right = Self::build_from_intervals(&intervals[mid + 1..]);
         node.max = *node.interval.end();
@@ -109,7 +202,7 @@ impl IntervalNode {
         }
         Some(node)
     }
-    fn contains(&self, integer: i64) -> bool {
+    pub fn contains(&self, integer: T) -> bool {
         // A double-ended queue is better than recursion ;)
         let mut queue = VecDeque::new();
         queue.push_back(self);
@@ -126,6 +219,271 @@ impl IntervalNode {
         }
         false
     }
+    /// Returns the integers that aren't contained in any interval of the tree, in the order they were given.
+    pub fn uncovered(&self, integers: &[T]) -> Vec<T> {
+        integers.iter().copied().filter(|&i| !self.contains(i)).collect()
+    }
+    /// Inserts a batch of unweighted intervals into the tree. Every interval is inserted like in a regular binary
+    /// search tree, which may unbalance it, so the whole tree is rebuilt once its height exceeds twice the height
+    /// of a balanced tree with the same number of intervals. This keeps the queries at O(log n) as the tree grows.
+    pub fn extend(&mut self, new: Vec<RangeInclusive<T>>) {
+        for interval in new {
+            self.insert(interval, 1);
+        }
+        let len = self.len();
+        let balanced_height = (usize::BITS - len.leading_zeros()) as usize;
+        if self.height() > 2 * balanced_height {
+            let mut intervals = Vec::with_capacity(len);
+            self.collect_in_order(&mut intervals);
+            *self = *Self::build_from_intervals(&intervals).expect("no interval provided");
+        }
+    }
+    // Walks down to the empty spot for the interval by its start, raising the max of every node on the way
+    fn insert(&mut self, interval: RangeInclusive<T>, weight: i64) {
+        let mut node = self;
+        loop {
+            node.max = node.max.max(*interval.end());
+            let child = if interval.start() < node.interval.start() { &mut node.left } else { &mut node.right };
+            if child.is_none() {
+                let max = *interval.end();
+                *child = Some(Box::new(IntervalNode { interval, weight, max, left: None, right: None }));
+                return;
+            }
+            node = child.as_mut().unwrap();
+        }
+    }
+    // The intervals sorted by their start, which is the order build_from_intervals expects
+    fn collect_in_order(&self, intervals: &mut Vec<(RangeInclusive<T>, i64)>) {
+        let mut stack = Vec::new();
+        let mut current = Some(self);
+        while current.is_some() || !stack.is_empty() {
+            while let Some(n) = current {
+                stack.push(n);
+                current = n.left.as_deref();
+            }
+            let n = stack.pop().unwrap();
+            intervals.push((n.interval.clone(), n.weight));
+            current = n.right.as_deref();
+        }
+    }
+    /// Returns the number of intervals in the tree.
+    pub fn len(&self) -> usize {
+        let mut count = 0;
+        let mut stack = vec![self];
+        while let Some(n) = stack.pop() {
+            count += 1;
+            stack.extend(n.left.as_deref());
+            stack.extend(n.right.as_deref());
+        }
+        count
+    }
+    /// Returns the number of nodes on the longest path from the root to a leaf.
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut stack = vec![(self, 1)];
+        while let Some((n, depth)) = stack.pop() {
+            height = height.max(depth);
+            stack.extend(n.left.as_deref().map(|l| (l, depth + 1)));
+            stack.extend(n.right.as_deref().map(|r| (r, depth + 1)));
+        }
+        height
+    }
+}
+
+impl IntervalNode {
+    /// Sums the weights of all intervals containing the point. Unlike `contains`, the search is pruned: a subtree
+    /// is skipped if its largest endpoint is below the point, and the right subtree is also skipped if the node's
+    /// interval starts after the point, since the intervals in the right subtree start even later.
+    pub fn total_weight_at(&self, point: i64) -> i64 {
+        let mut total = 0;
+        let mut queue = VecDeque::new();
+        queue.push_back(self);
+        while let Some(n) = queue.pop_front() {
+            if n.max < point {
+                continue;
+            }
+            if n.interval.contains(&point) {
+                total += n.weight;
+            }
+            if let Some(ref ln) = n.left {
+                queue.push_back(ln);
+            }
+            if let Some(ref rn) = n.right && *n.interval.start() <= point {
+                queue.push_back(rn);
+            }
+        }
+        total
+    }
+    /// Checks if the query overlaps at least one interval of the tree, where the query is either a single
+    /// integer or an inclusive range of integers. The search is pruned like in `total_weight_at`.
+    pub fn query<Q: Query>(&self, q: Q) -> bool {
+        let (start, end) = q.bounds();
+        if start > end {
+            return false;
+        }
+        let mut queue = VecDeque::new();
+        queue.push_back(self);
+        while let Some(n) = queue.pop_front() {
+            if n.max < start {
+                continue;
+            }
+            if *n.interval.start() <= end && *n.interval.end() >= start {
+                return true;
+            }
+            if let Some(ref ln) = n.left {
+                queue.push_back(ln);
+            }
+            if let Some(ref rn) = n.right && *n.interval.start() <= end {
+                queue.push_back(rn);
+            }
+        }
+        false
+    }
+    /// Returns the `k` intervals whose starts are the nearest to the point, from the nearest to the farthest, where
+    /// the interval with the smaller start comes first in a tie. Since the tree is ordered by the starts, the intervals
+    /// starting before and after the point are walked outwards from it, like two in-order traversals in opposite
+    /// directions, so only O(log n + k) nodes are visited.
+    pub fn nearest_intervals(&self, point: i64, k: usize) -> Vec<RangeInclusive<i64>> {
+        // The nodes left to visit, with the next predecessor or successor of the point on top
+        let mut before: Vec<&IntervalNode> = Vec::new();
+        let mut after: Vec<&IntervalNode> = Vec::new();
+        let mut current = Some(self);
+        while let Some(n) = current {
+            if *n.interval.start() < point {
+                before.push(n);
+                current = n.right.as_deref();
+            } else {
+                after.push(n);
+                current = n.left.as_deref();
+            }
+        }
+        let distance = |n: &IntervalNode| (i128::from(*n.interval.start()) - i128::from(point)).unsigned_abs();
+        let mut nearest = Vec::with_capacity(k);
+        while nearest.len() < k {
+            let take_before = match (before.last(), after.last()) {
+                (Some(b), Some(a)) => distance(b) <= distance(a),
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let n = if take_before {
+                let n = before.pop().unwrap();
+                // The next predecessor is the rightmost node of the left subtree
+                let mut current = n.left.as_deref();
+                while let Some(c) = current {
+                    before.push(c);
+                    current = c.right.as_deref();
+                }
+                n
+            } else {
+                let n = after.pop().unwrap();
+                // The next successor is the leftmost node of the right subtree
+                let mut current = n.right.as_deref();
+                while let Some(c) = current {
+                    after.push(c);
+                    current = c.left.as_deref();
+                }
+                n
+            };
+            nearest.push(n.interval.clone());
+        }
+        nearest
+    }
+}
+
+/// A query against the interval tree, either a point (`i64`) or a range (`RangeInclusive<i64>`).
+pub trait Query {
+    /// Returns the inclusive `(start, end)` bounds of the query. A point is a range of a single integer.
+    fn bounds(&self) -> (i64, i64);
+}
+
+impl Query for i64 {
+    fn bounds(&self) -> (i64, i64) {
+        (*self, *self)
+    }
+}
+
+impl Query for RangeInclusive<i64> {
+    fn bounds(&self) -> (i64, i64) {
+        (*self.start(), *self.end())
+    }
+}
+
+/// Counts the query integers read line by line from `reader` that are contained in at least one interval of the tree.
+/// Unlike puzzle 1, the integers are never collected into memory, so the query section may be arbitrarily large.
+/// Blank lines are skipped.
+#[must_use]
+pub fn count_contained_stream(tree: &IntervalNode, reader: impl BufRead) -> usize {
+    reader
+        .lines()
+        .map(|l| l.expect("failed to read query line"))
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.trim().parse::<i64>().expect("failed to parse 64-bit integer"))
+        .filter(|i| tree.contains(*i))
+        .count()
+}
+
+/// Counts the integers that are contained in at least one interval of the tree, using the pruned `query` search.
+/// Like puzzle 1, every occurrence of an integer is counted, so an integer listed twice is counted twice.
+#[must_use]
+pub fn count_contained(tree: &IntervalNode, integers: &[i64]) -> usize {
+    integers.iter().filter(|&&i| tree.query(i)).count()
+}
+
+/// Counts every occurrence of an integer that is contained in at least one interval of the tree, so an integer
+/// listed twice is counted twice. This is the same count as `count_contained`, named explicitly as the counterpart
+/// of `count_distinct_contained`, so that callers can state which of the two behaviors they rely on.
+#[must_use]
+pub fn count_contained_with_duplicates(tree: &IntervalNode, integers: &[i64]) -> usize {
+    count_contained(tree, integers)
+}
+
+/// Counts the distinct integers that are contained in at least one interval of the tree, so repeated integers are
+/// only counted once.
+#[must_use]
+pub fn count_distinct_contained(tree: &IntervalNode, integers: &[i64]) -> usize {
+    let distinct: HashSet<i64> = integers.iter().copied().collect();
+    distinct.into_iter().filter(|&i| tree.query(i)).count()
+}
+
+/// Same as `count_contained`, but without an interval tree: the intervals are consolidated like in puzzle 2 and each
+/// integer is then looked up with a binary search over the sorted, disjoint intervals. This is the simpler option
+/// mentioned in puzzle 1, and gives the same count in O(n log n + q log n).
+#[must_use]
+pub fn count_contained_linear(intervals: &[RangeInclusive<i64>], integers: &[i64]) -> usize {
+    let sweep_line = SweepLine::from(intervals.to_vec());
+    integers.iter().filter(|&&i| sweep_line.contains(i)).count()
+}
+
+/// The intervals consolidated into sorted, disjoint intervals, as if sweeping a line over the number line from left
+/// to right and recording where coverage starts and stops. Once built, it answers both puzzles for static intervals:
+/// whether a point is covered (puzzle 1) with a binary search, and how many integers are covered (puzzle 2).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepLine<T = i64> {
+    merged: Vec<RangeInclusive<T>>,
+}
+
+impl<T: Copy + Ord + Into<i64>> From<Vec<RangeInclusive<T>>> for SweepLine<T> {
+    fn from(intervals: Vec<RangeInclusive<T>>) -> Self {
+        SweepLine { merged: merge_intervals(intervals) }
+    }
+}
+
+impl<T: Copy + Ord + Into<i64>> SweepLine<T> {
+    /// Checks if the point is covered by at least one of the intervals.
+    pub fn contains(&self, point: T) -> bool {
+        // The first interval that ends at or after the point is the only one that may contain it
+        let index = self.merged.partition_point(|r| *r.end() < point);
+        self.merged.get(index).is_some_and(|r| *r.start() <= point)
+    }
+
+    /// Counts the integers covered by at least one of the intervals.
+    pub fn covered_count(&self) -> u128 {
+        self.merged
+            .iter()
+            .map(|i| interval_width((*i.start()).into(), (*i.end()).into()))
+            .sum()
+    }
 }
 
 /// # Puzzle 2
@@ -144,36 +502,168 @@ impl IntervalNode {
 /// 
 #[must_use]
 pub fn puzzle2(input: &str) -> u128 {
-    let (intervals_str, _) = input
-        .trim()
-        .split_once("\n\n")
+    let (intervals_str, _) = split_sections(input)
         .expect("input must contain intervals and integers separated by an empty line");
-    let mut intervals: Vec<RangeInclusive<i64>> = intervals_str
-        .split('\n')
-        .map(|i| parse_inclusive_range(i).unwrap())
-        .collect();
-    if intervals.len() == 0 {
-        return 0;
+    match parse_intervals_narrowed(intervals_str) {
+        Intervals::Narrow(narrow) => SweepLine::from(narrow).covered_count(),
+        Intervals::Wide(intervals) => SweepLine::from(intervals).covered_count(),
+    }
+}
+
+// Sorts the intervals and consolidates the ones that overlap or are adjacent, as described in puzzle 2
+fn merge_intervals<T: Copy + Ord + Into<i64>>(mut intervals: Vec<RangeInclusive<T>>) -> Vec<RangeInclusive<T>> {
+    let mut merged = Vec::new();
+    if intervals.is_empty() {
+        return merged;
     }
     intervals.sort_by_key(|n| *n.start());
-    let mut total: u128 = 0;
     let mut current_start = intervals[0].start();
     let mut current_end = intervals[0].end();
     for i in intervals.iter().skip(1) {
-        if *i.start() <= current_end + 1 {
+        if i128::from((*i.start()).into()) <= i128::from((*current_end).into()) + 1 {
             current_end = current_end.max(i.end());
         } else {
-            total += (current_end - current_start + 1) as u128;
+            merged.push(*current_start..=*current_end);
             current_start = i.start();
             current_end = i.end();
         }
     }
-    total += (current_end - current_start + 1) as u128;
-    total
+    merged.push(*current_start..=*current_end);
+    merged
+}
+
+/// Returns the maximal sub-ranges of the universe that aren't covered by any of the intervals, in ascending order.
+/// The intervals are consolidated like in puzzle 2 and the gaps are whatever is left between them.
+#[must_use]
+pub fn gaps_within(intervals: &[RangeInclusive<i64>], universe: RangeInclusive<i64>) -> Vec<RangeInclusive<i64>> {
+    let mut gaps = Vec::new();
+    if universe.is_empty() {
+        return gaps;
+    }
+    // The start of the part of the universe that hasn't been checked yet, or None once the universe is exhausted
+    let mut next_start = Some(*universe.start());
+    for i in merge_intervals(intervals.to_vec()) {
+        let Some(start) = next_start else {
+            break;
+        };
+        if *i.end() < start {
+            continue;
+        }
+        if *i.start() > *universe.end() {
+            break;
+        }
+        if *i.start() > start {
+            gaps.push(start..=*i.start() - 1);
+        }
+        next_start = i.end().checked_add(1).filter(|s| s <= universe.end());
+    }
+    if let Some(start) = next_start {
+        gaps.push(start..=*universe.end());
+    }
+    gaps
+}
+
+/// Same as `gaps_within`, but the universe spans from the smallest start to the largest end of the intervals, so only
+/// the gaps between the intervals are returned.
+#[must_use]
+pub fn gaps(intervals: &[RangeInclusive<i64>]) -> Vec<RangeInclusive<i64>> {
+    let min_start = intervals.iter().map(|i| *i.start()).min();
+    let max_end = intervals.iter().map(|i| *i.end()).max();
+    match (min_start, max_end) {
+        (Some(min_start), Some(max_end)) => gaps_within(intervals, min_start..=max_end),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the maximal ranges of integers covered by the intervals of `a` but by none of the intervals of `b`, in
+/// ascending order. Both sets of intervals are consolidated like in puzzle 2 and swept together, cutting every
+/// interval of `a` at the intervals of `b` overlapping it.
+#[must_use]
+pub fn difference_intervals(a: &[RangeInclusive<i64>], b: &[RangeInclusive<i64>]) -> Vec<RangeInclusive<i64>> {
+    let b = merge_intervals(b.to_vec());
+    let mut difference = Vec::new();
+    // The first interval of b that may still overlap the current interval of a
+    let mut j = 0;
+    for i in merge_intervals(a.to_vec()) {
+        while j < b.len() && *b[j].end() < *i.start() {
+            j += 1;
+        }
+        // The start of the part of the interval that hasn't been cut yet, or None once the interval is exhausted
+        let mut next_start = Some(*i.start());
+        for cut in b[j..].iter().take_while(|cut| *cut.start() <= *i.end()) {
+            let Some(start) = next_start else {
+                break;
+            };
+            if *cut.start() > start {
+                difference.push(start..=*cut.start() - 1);
+            }
+            next_start = cut.end().checked_add(1).filter(|s| s <= i.end());
+        }
+        if let Some(start) = next_start {
+            difference.push(start..=*i.end());
+        }
+    }
+    difference
+}
+
+/// Counts the integers covered by the intervals of `a` but by none of the intervals of `b`.
+#[must_use]
+pub fn difference_count(a: &[RangeInclusive<i64>], b: &[RangeInclusive<i64>]) -> u128 {
+    difference_intervals(a, b)
+        .iter()
+        .map(|i| interval_width(*i.start(), *i.end()))
+        .sum()
+}
+
+// The width is computed in 128 bits, since the difference of two 64-bit endpoints doesn't always fit in 64 bits
+fn interval_width(start: i64, end: i64) -> u128 {
+    (i128::from(end) - i128::from(start) + 1) as u128
+}
+
+/// Parses intervals from a JSON array of `[start, end]` pairs, e.g. `[[3,5],[10,14]]`.
+#[cfg(feature = "json")]
+pub fn parse_intervals_json(s: &str) -> Result<Vec<RangeInclusive<i64>>, String> {
+    let pairs: Vec<(i64, i64)> = serde_json::from_str(s)
+        .map_err(|e| format!("invalid JSON intervals: {}", e))?;
+    Ok(pairs.into_iter().map(|(start, end)| start..=end).collect())
+}
+
+/// # Puzzle 2 (JSON)
+///
+/// Same as puzzle 2, but the input is a JSON array of intervals as accepted by `parse_intervals_json`,
+/// without the section of integers.
+#[cfg(feature = "json")]
+#[must_use]
+pub fn puzzle2_json(input: &str) -> u128 {
+    let intervals = parse_intervals_json(input).expect("failed to parse JSON intervals");
+    SweepLine::from(intervals).covered_count()
+}
+
+/// A merged interval of the coverage, which serializes as an object with `start` and `end` fields.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CoveredInterval {
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Consolidates the intervals like puzzle 2 and returns the disjoint intervals covering the same integers in
+/// ascending order, ready to be serialized, e.g. with `serde_json::to_string`.
+#[cfg(feature = "serde")]
+#[must_use]
+pub fn merged_serializable(intervals: Vec<RangeInclusive<i64>>) -> Vec<CoveredInterval> {
+    merge_intervals(intervals)
+        .into_iter()
+        .map(|r| CoveredInterval { start: *r.start(), end: *r.end() })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use crate::utils;
+
     use super::*;
 
     const TEST_INPUT: &str = "3-5
@@ -197,4 +687,258 @@ mod tests {
     fn test_puzzle2() {
         assert_eq!(puzzle2(TEST_INPUT), 14);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_gaps_within() {
+        let intervals = [3..=5, 10..=14, 16..=20, 12..=18];
+        // Fully covered
+        assert_eq!(gaps_within(&intervals, 10..=20), vec![]);
+        assert_eq!(gaps_within(&intervals, 4..=4), vec![]);
+        // Fully uncovered
+        assert_eq!(gaps_within(&intervals, 21..=30), vec![21..=30]);
+        assert_eq!(gaps_within(&[], 1..=9), vec![1..=9]);
+        // Partially covered
+        assert_eq!(gaps_within(&intervals, 0..=25), vec![0..=2, 6..=9, 21..=25]);
+        assert_eq!(gaps_within(&intervals, 4..=12), vec![6..=9]);
+        assert_eq!(gaps_within(&[i64::MIN..=0], i64::MIN..=i64::MAX), vec![1..=i64::MAX]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_intervals() {
+        let json = "[[3, 5], [10, 14], [16, 20], [12, 18]]";
+        assert_eq!(parse_intervals_json(json), Ok(vec![3..=5, 10..=14, 16..=20, 12..=18]));
+        assert_eq!(puzzle2_json(json), puzzle2(TEST_INPUT));
+        assert!(parse_intervals_json("[[3, 5], [10]]").is_err());
+        assert!(parse_intervals_json("3-5").is_err());
+    }
+
+    #[cfg(all(feature = "serde", feature = "json"))]
+    #[test]
+    fn test_merged_serializable() {
+        let merged = merged_serializable(vec![3..=5, 10..=14, 16..=20, 12..=18]);
+        let json = serde_json::to_string(&merged).unwrap();
+        assert_eq!(json, r#"[{"start":3,"end":5},{"start":10,"end":20}]"#);
+        let parsed: Vec<CoveredInterval> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, merged);
+    }
+
+    #[test]
+    fn test_uncovered() {
+        let tree = IntervalNode::from(vec![3..=5, 10..=14, 16..=20, 12..=18]);
+        let integers = [1, 5, 8, 11, 17, 32];
+        assert_eq!(tree.uncovered(&integers), vec![1, 8, 32]);
+        assert_eq!(integers.len() - tree.uncovered(&integers).len(), puzzle1(TEST_INPUT) as usize);
+        assert!(tree.uncovered(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_nearest_intervals() {
+        let tree = IntervalNode::from(vec![3..=5, 10..=14, 16..=20, 12..=18]);
+        // The starts 10 and 12 are both 1 away from 11
+        assert_eq!(tree.nearest_intervals(11, 2), vec![10..=14, 12..=18]);
+        assert_eq!(tree.nearest_intervals(17, 2), vec![16..=20, 12..=18]);
+        assert_eq!(tree.nearest_intervals(0, 1), vec![3..=5]);
+        assert_eq!(tree.nearest_intervals(100, 10), vec![16..=20, 12..=18, 10..=14, 3..=5]);
+        // Matches sorting all the intervals by their distance, with the later insertions unbalancing the tree. The
+        // starts are all distinct, so the order is unambiguous
+        let mut tree = IntervalNode::from(vec![200..=210]);
+        let intervals: Vec<RangeInclusive<i64>> = (0..40).map(|i| (i * 37 % 101)..=(i * 37 % 101 + 5)).collect();
+        tree.extend(intervals.clone());
+        let mut all = intervals;
+        all.push(200..=210);
+        for point in [-5, 0, 33, 50, 77, 150, 300] {
+            let mut expected = all.clone();
+            expected.sort_by_key(|r| ((r.start() - point).abs(), *r.start()));
+            assert_eq!(tree.nearest_intervals(point, 7), expected[..7]);
+        }
+    }
+
+    #[test]
+    fn test_gaps() {
+        let intervals = vec![3..=5, 10..=14, 16..=20, 12..=18];
+        assert_eq!(gaps(&intervals), vec![6..=9]);
+        assert_eq!(gaps(&intervals), gaps_within(&intervals, 3..=20));
+        assert_eq!(gaps(&[1..=2, 5..=5, 9..=12]), vec![3..=4, 6..=8]);
+        assert_eq!(gaps(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_difference() {
+        let a = vec![3..=5, 10..=14, 16..=20, 12..=18];
+        // Partial overlaps cut both ends, and an interval inside a splits it in two
+        assert_eq!(difference_intervals(&a, &[4..=11, 15..=15, 19..=30]), vec![3..=3, 12..=14, 16..=18]);
+        assert_eq!(difference_count(&a, &[4..=11, 15..=15, 19..=30]), 7);
+        // A single interval of b spanning two intervals of a
+        assert_eq!(difference_intervals(&a, &[5..=10]), vec![3..=4, 11..=20]);
+        // Full overlap
+        assert_eq!(difference_intervals(&a, &[0..=100]), vec![]);
+        assert_eq!(difference_count(&a, &[3..=20]), 0);
+        // No overlap
+        assert_eq!(difference_intervals(&a, &[6..=9, 21..=25]), vec![3..=5, 10..=20]);
+        assert_eq!(difference_count(&a, &[]), puzzle2(TEST_INPUT));
+        assert_eq!(difference_intervals(&[i64::MIN..=i64::MAX], &[0..=i64::MAX]), vec![i64::MIN..=-1]);
+    }
+
+    #[test]
+    fn test_crlf_sections() {
+        let input = TEST_INPUT.replace('\n', "\r\n");
+        assert_eq!(puzzle1(&input), 3);
+        assert_eq!(puzzle2(&input), 14);
+    }
+
+    #[test]
+    fn test_multiple_blank_lines() {
+        let input = TEST_INPUT.replace("\n\n", "\n\n\n");
+        assert_eq!(puzzle1(&input), 3);
+        assert_eq!(puzzle2(&input), 14);
+        let input = TEST_INPUT.replace("\n\n", "\r\n  \r\n\r\n");
+        assert_eq!(puzzle1(&input), 3);
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines() {
+        let input = "# fresh ingredient ranges
+3-5
+
+# the second batch
+10-14
+  # overlaps with the next one
+16-20
+
+12-18
+
+
+1
+5
+8
+11
+17
+32";
+        assert_eq!(puzzle1(input), 3);
+        assert_eq!(puzzle2(input), 14);
+    }
+
+    #[test]
+    fn test_parse_inclusive_range() {
+        assert_eq!(parse_inclusive_range("3-5"), Ok(3..=5));
+        assert_eq!(parse_inclusive_range("-5--3"), Ok(-5..=-3));
+        assert_eq!(parse_inclusive_range("-5-3"), Ok(-5..=3));
+        assert!(parse_inclusive_range("3").is_err());
+        assert!(parse_inclusive_range("3-5-7").is_err());
+    }
+
+    #[test]
+    fn test_puzzle2_huge_intervals() {
+        assert_eq!(puzzle2("-9000000000000000000-9000000000000000000\n\n1"), 18000000000000000001);
+        assert_eq!(
+            puzzle2("-9223372036854775808--1\n0-9223372036854775807\n\n1"),
+            18446744073709551616
+        );
+    }
+
+    #[test]
+    fn test_total_weight_at() {
+        let tree = IntervalNode::from(vec![(1..=10, 5), (4..=6, -2), (5..=20, 7), (6..=6, 3), (30..=40, 100)]);
+        assert_eq!(tree.total_weight_at(6), 13);
+        assert_eq!(tree.total_weight_at(3), 5);
+        assert_eq!(tree.total_weight_at(25), 0);
+        let unweighted = IntervalNode::from(vec![3..=5, 10..=14, 16..=20, 12..=18]);
+        assert_eq!(unweighted.total_weight_at(17), 2);
+    }
+
+    #[test]
+    fn test_query() {
+        let tree = IntervalNode::from(vec![3..=5, 10..=14, 16..=20, 12..=18]);
+        assert!(tree.query(5));
+        assert!(tree.query(17));
+        assert!(!tree.query(8));
+        assert!(!tree.query(32));
+        assert!(tree.query(6..=10));
+        assert!(tree.query(-10..=3));
+        assert!(!tree.query(6..=9));
+        assert!(!tree.query(21..=100));
+        assert!(!tree.query(14..=12));
+    }
+
+    #[test]
+    fn test_narrow_intervals() {
+        let intervals_str = split_sections(TEST_INPUT).unwrap().0;
+        assert_eq!(parse_intervals_narrowed(intervals_str), Intervals::Narrow(vec![3..=5, 10..=14, 16..=20, 12..=18]));
+        // Once an endpoint doesn't fit, the intervals parsed so far are widened
+        assert_eq!(
+            parse_intervals_narrowed("3-5\n0-2147483648\n-1-1"),
+            Intervals::Wide(vec![3..=5, 0..=i64::from(i32::MAX) + 1, -1..=1])
+        );
+        assert!(size_of::<RangeInclusive<i32>>() < size_of::<RangeInclusive<i64>>());
+        let intervals: Vec<RangeInclusive<i64>> = parse_intervals(intervals_str).collect();
+        // The 32-bit path is taken for the sample, so it must match the 64-bit tree and merge
+        let tree = IntervalNode::from(intervals.clone());
+        assert_eq!(puzzle1(TEST_INPUT) as usize, count_contained(&tree, &[1, 5, 8, 11, 17, 32]));
+        assert_eq!(puzzle2(TEST_INPUT), SweepLine::from(intervals).covered_count());
+        // An integer beyond 32 bits is never contained in the smaller sweep line
+        assert_eq!(puzzle1("1-5\n\n3\n4294967299"), 1);
+        assert_eq!(puzzle1("1-5\n3-4294967299\n\n3\n4294967299"), 2);
+        assert_eq!(puzzle2("1-5\n3-4294967299\n\n3"), 4294967299);
+    }
+
+    #[test]
+    fn test_sweep_line() {
+        let (intervals_str, integers_str) = split_sections(TEST_INPUT).unwrap();
+        let sweep_line = SweepLine::from(parse_intervals(intervals_str).collect::<Vec<_>>());
+        let contained = integers_str
+            .lines()
+            .filter(|i| sweep_line.contains(i.parse().unwrap()))
+            .count();
+        assert_eq!(contained as u128, puzzle1(TEST_INPUT));
+        assert_eq!(sweep_line.covered_count(), puzzle2(TEST_INPUT));
+        assert!(sweep_line.contains(16) && !sweep_line.contains(21) && !sweep_line.contains(i64::MIN));
+        assert_eq!(SweepLine::from(vec![1..=2, 3..=4, 7..=7]).covered_count(), 5);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut tree = IntervalNode::from(vec![3..=5, 10..=14, 16..=20, 12..=18]);
+        let mut intervals = vec![3..=5, 10..=14, 16..=20, 12..=18];
+        // Batches of ascending intervals would degenerate into a linked list without the rebuilds
+        for batch in 0..10 {
+            let new: Vec<RangeInclusive<i64>> = (0..50).map(|i| {
+                let start = 100 + batch * 1000 + i * 10;
+                start..=start + 3
+            }).collect();
+            intervals.extend(new.clone());
+            tree.extend(new);
+            let balanced_height = (usize::BITS - tree.len().leading_zeros()) as usize;
+            assert_eq!(tree.len(), intervals.len());
+            assert!(tree.height() <= 2 * balanced_height, "height {} for {} intervals", tree.height(), tree.len());
+        }
+        let integers: Vec<i64> = (-10..11_000).collect();
+        assert_eq!(count_contained(&tree, &integers), count_contained_linear(&intervals, &integers));
+        assert!(tree.contains(9103) && !tree.contains(9107));
+    }
+
+    #[test]
+    fn test_count_contained_linear_matches_tree() {
+        let intervals = vec![3..=5, 10..=14, 16..=20, 12..=18, -7..=-2];
+        let integers = [-8, -7, -3, 1, 5, 8, 11, 17, 32];
+        let tree = IntervalNode::from(intervals.clone());
+        assert_eq!(count_contained(&tree, &integers), 5);
+        assert_eq!(count_contained_linear(&intervals, &integers), 5);
+    }
+
+    #[test]
+    fn test_count_contained_stream() {
+        let tree = IntervalNode::from(vec![3..=5, 10..=14, 16..=20, 12..=18]);
+        let query_path = utils::string_to_file("1\n5\n8\n11\n17\n32\n");
+        let reader = BufReader::new(File::open(query_path).unwrap());
+        assert_eq!(count_contained_stream(&tree, reader), 3);
+    }
+
+    #[test]
+    fn test_duplicate_queries() {
+        let tree = IntervalNode::from(vec![3..=5, 10..=14]);
+        let integers = [4, 4, 4, 11, 1, 1, 14, 20];
+        assert_eq!(count_contained_with_duplicates(&tree, &integers), 5);
+        assert_eq!(count_distinct_contained(&tree, &integers), 3);
+    }
+}
@@ -1,6 +1,46 @@
 //! # Day 6: Trash Compactor ([challenge description](https://adventofcode.com/2025/day/6))
 
-use std::{fs::File, io::{BufRead, BufReader}, os::unix::fs::FileExt};
+use std::{fmt::Display, fs::File, io::{BufRead, BufReader}, iter::{Product, Sum}, ops::AddAssign, os::unix::fs::FileExt, str::FromStr};
+
+// Random access to the bytes of the input file. Reading from the file issues a read_at system call per byte,
+// whereas a memory-mapped file (with the mmap feature) is read like a regular slice of bytes.
+trait ByteSource {
+    fn byte_at(&self, offset: u64) -> u8;
+}
+
+impl ByteSource for File {
+    fn byte_at(&self, offset: u64) -> u8 {
+        let mut buf = [0u8; 1];
+        self.read_at(&mut buf, offset).expect("failed to read the next byte");
+        buf[0]
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl ByteSource for memmap2::Mmap {
+    fn byte_at(&self, offset: u64) -> u8 {
+        self[offset as usize]
+    }
+}
+
+// Scans the input file for the offset at which every line starts, followed by the length of the file
+fn scan_newline_offsets(input_path: &str) -> (File, Vec<u64>) {
+    let input_file = File::open(input_path).expect("failed to read input for day 6");
+    let mut reader = BufReader::new(input_file);
+    let mut newline_offsets: Vec<u64> = vec![0];
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        // Note: I realized that read_until still puts the entire first line in memory, so it might not be able to handle 
+        // extremely long inputs after all. I'm keeping it as is but I'll take a more efficient approach in day 7.
+        let bytes_read = reader.read_until(b'\n', &mut buf).expect("failed to scan input file for newlines");
+        if bytes_read == 0 {
+            break;
+        }
+        newline_offsets.push(newline_offsets.last().unwrap() + bytes_read as u64);
+    }
+    (reader.into_inner(), newline_offsets)
+}
 
 /// # Puzzle 1
 /// 
@@ -30,45 +70,96 @@ use std::{fs::File, io::{BufRead, BufReader}, os::unix::fs::FileExt};
 /// 
 #[must_use]
 pub fn puzzle1(input_path: &str) -> i64 {
-    let mut input_file = File::open(input_path).expect("failed to read input for day 6");
-    let mut reader = BufReader::new(input_file);
-    let mut newline_offsets: Vec<u64> = vec![0];
-    let mut buf = Vec::new();
-    loop {
-        buf.clear();
-        // Note: I realized that read_until still puts the entire first line in memory, so it might not be able to handle 
-        // extremely long inputs after all. I'm keeping it as is but I'll take a more efficient approach in day 7.
-        let bytes_read = reader.read_until(b'\n', &mut buf).expect("failed to scan input file for newlines");
-        if bytes_read == 0 {
-            break;
-        }
-        newline_offsets.push(newline_offsets.last().unwrap() + bytes_read as u64);
-    }
+    puzzle1_checked(input_path).expect("failed to parse number found in a cell")
+}
+
+/// # Puzzle 1 (checked)
+///
+/// Same as puzzle 1, but a cell that isn't a valid number is returned as an error mentioning the index of its
+/// column (counted from 0 on the left) and the offending characters, instead of panicking.
+pub fn puzzle1_checked(input_path: &str) -> Result<i64, String> {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    puzzle1_from_source(&input_file, &newline_offsets, ScanOptions::default())
+}
+
+/// # Puzzle 1 (digit grouping)
+///
+/// Same as puzzle 1, but the numbers may contain thousands separators, e.g. `1,234` or `1_234`. Commas and
+/// underscores inside a cell are stripped before it's parsed.
+#[must_use]
+pub fn puzzle1_grouped(input_path: &str) -> i64 {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    puzzle1_from_source(&input_file, &newline_offsets, ScanOptions { grouping: true, ..Default::default() }).expect("failed to parse number found in a cell")
+}
+
+/// # Puzzle 1 (floating point)
+///
+/// Same as puzzle 1, but the cells are floating point numbers, which may be written in scientific notation,
+/// e.g. `1.5e3` or `2E-2`. A + character right after the exponent marker is part of the number rather than
+/// the operation symbol.
+#[must_use]
+pub fn puzzle1_float(input_path: &str) -> f64 {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    puzzle1_from_source(&input_file, &newline_offsets, ScanOptions::default()).expect("failed to parse number found in a cell")
+}
+
+/// # Puzzle 1 (empty operands)
+///
+/// Same as puzzle 1, but a column with an operation symbol and no numbers, e.g. when the lines of numbers are
+/// shorter than the line of operations, contributes the identity of its operation (0 for + and 1 for *) instead
+/// of ending the scan.
+#[must_use]
+pub fn puzzle1_empty_identity(input_path: &str) -> i64 {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    puzzle1_from_source(&input_file, &newline_offsets, ScanOptions { empty_identity: true, ..Default::default() })
+        .expect("failed to parse number found in a cell")
+}
+
+// The variations of the puzzle 1 scan, which are all off for the puzzle itself
+#[derive(Debug, Default, Clone, Copy)]
+struct ScanOptions {
+    // Commas and underscores inside a cell are thousands separators
+    grouping: bool,
+    // A column with an operation but no numbers contributes the identity of the operation
+    empty_identity: bool,
+}
+
+// The numbers of puzzle 1 are integers, but the scan works the same for any type that can be parsed from a cell
+fn puzzle1_from_source<T>(source: &impl ByteSource, newline_offsets: &[u64], options: ScanOptions) -> Result<T, String>
+where
+    T: FromStr + Copy + Default + AddAssign + Sum + Product,
+    T::Err: Display,
+{
     let mut current_offsets: Vec<_> = newline_offsets
         .windows(2)
         .map(|w| w[0]..w[1])
         .collect();
-    let offsets_len = current_offsets.len();
-    input_file = reader.into_inner();
-    let mut total_sum: i64 = 0;
+    let mut total_sum = T::default();
     // Keep looping until no new column can be found
-    loop {
-        let mut numbers: Vec<i64> = vec![];
+    for column in 0.. {
+        let mut numbers: Vec<T> = vec![];
         let mut operation: Option<Operations> = None;
         // Scan for the next value in each line
-        for (i, iter) in &mut current_offsets.iter_mut().enumerate() {
+        for iter in &mut current_offsets {
             let mut num_string = String::new();
-            let is_last_offset = i == offsets_len - 1;
             // Keep reading the line until either one operation character or 
-            // one or more digits (potentially negative) followed by a space are found
+            // one or more digits (potentially negative) followed by a space are found.
+            // The operation may sit on any line of the column, every other cell is a number
             for char_offset in iter.by_ref() {
-                let mut buf = [0u8; 1];
-                input_file.read_at(&mut buf, char_offset).expect("failed to read the next byte");
-                let c = buf[0] as char;
+                let c = source.byte_at(char_offset) as char;
                 if c.is_whitespace() && !num_string.is_empty() {
-                    numbers.push(num_string.parse::<i64>().expect("failed to parse number found in a cell"));
+                    if options.grouping {
+                        num_string.retain(|c| c != ',' && c != '_');
+                    }
+                    let number = num_string
+                        .parse::<T>()
+                        .map_err(|e| format!("invalid number {:?} in column {}: {}", num_string, column, e))?;
+                    numbers.push(number);
                     break;
-                } else if !is_last_offset && (c.is_ascii_digit() || c == '-') {
+                } else if c.is_whitespace() {
+                    continue;
+                } else if c == '+' && num_string.ends_with(['e', 'E']) {
+                    // The sign of an exponent in scientific notation
                     num_string.push(c);
                 } else if c == '+' {
                     operation = Some(Operations::Add);
@@ -76,17 +167,21 @@ pub fn puzzle1(input_path: &str) -> i64 {
                 } else if  c == '*' {
                     operation = Some(Operations::Multiply);
                     break;
+                } else {
+                    // Anything else is kept, so that an invalid cell fails to parse rather than being skipped
+                    num_string.push(c);
                 }
             }
         }
-        if let Some(o) = operation && !numbers.is_empty() {
+        // The sum and product of no numbers are 0 and 1 respectively
+        if let Some(o) = operation && (options.empty_identity || !numbers.is_empty()) {
             match o {
                 Operations::Add => {
-                    let res: i64 = numbers.iter().sum();
+                    let res: T = numbers.iter().copied().sum();
                     total_sum += res;
                 },
                 Operations::Multiply => {
-                    let res: i64 = numbers.iter().product();
+                    let res: T = numbers.iter().copied().product();
                     total_sum += res;
                 }
             }
@@ -95,7 +190,67 @@ pub fn puzzle1(input_path: &str) -> i64 {
             break;
         }
     }
-    total_sum
+    Ok(total_sum)
+}
+
+/// # Puzzle 1 (row-oriented)
+///
+/// Same as puzzle 1, but for input that has already been transposed into the row-oriented format described above,
+/// so no column scanning is needed. Every line holds the numbers of one problem separated by whitespace, followed by
+/// the operation symbol, e.g. `123 45 6 *`. Blank lines are skipped.
+#[must_use]
+pub fn puzzle1_row_oriented(input: &str) -> i64 {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            let mut cells: Vec<&str> = l.split_whitespace().collect();
+            let operation = match cells.pop() {
+                Some("+") => Operations::Add,
+                Some("*") => Operations::Multiply,
+                _ => panic!("every row must end with an operation symbol"),
+            };
+            let numbers = cells
+                .iter()
+                .map(|n| n.parse::<i64>().expect("failed to parse number found in a cell"));
+            match operation {
+                Operations::Add => numbers.sum::<i64>(),
+                Operations::Multiply => numbers.product(),
+            }
+        })
+        .sum()
+}
+
+/// # Input shape
+///
+/// A dry run that scans the input file once and reports the detected shape as `(line_count, column_count)`,
+/// where the column count is the number of operation symbols found on any line, i.e. the number of columns
+/// that will be computed, since the operation may sit on any row of its column. Blank lines are ignored. A
+/// column count that doesn't match the expected number of problems is a sign of a misaligned input.
+#[must_use]
+pub fn describe_input(input_path: &str) -> (usize, usize) {
+    let input_file = File::open(input_path).expect("failed to read input for day 6");
+    let mut reader = BufReader::new(input_file);
+    let mut line_count = 0;
+    let mut column_count = 0;
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let bytes_read = reader.read_until(b'\n', &mut buf).expect("failed to scan input file for newlines");
+        if bytes_read == 0 {
+            break;
+        }
+        if buf.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        line_count += 1;
+        // An operation is a cell of its own, unlike the + of a number like 1e+5
+        column_count += buf
+            .split(u8::is_ascii_whitespace)
+            .filter(|cell| *cell == b"+" || *cell == b"*")
+            .count();
+    }
+    (line_count, column_count)
 }
 
 #[derive(Debug)]
@@ -117,30 +272,46 @@ type Operations = ArithmeticEnum;
 /// ## Solution
 /// Some modifications need to be made to the solution of puzzle 1. Each line must be read from right to left and
 /// only one digit at a time. A number is fully formed as soon as the first whitespace is found after a digit in a 
-/// vertical column. Since the operation symbol is always at the bottom of the last sub-column, perform the operation 
-/// and increment the total sum as soon as it is found.
+/// vertical column. Once a fully blank sub-column separating two problems is reached, perform the operation of the
+/// problem with all of its numbers and increment the total sum.
+/// 
+/// Even though the sub-columns are visited from right to left, each sub-column is still read from top to bottom. The minus
+/// sign of a negative number sits at the top of its sub-column, so it is read before the digits and ends up in front of them.
 /// 
 #[must_use]
 pub fn puzzle2(input_path: &str) -> i64 {
-    let mut input_file = File::open(input_path).expect("failed to read input for day 6");
-    let mut reader = BufReader::new(input_file);
-    let mut newline_offsets: Vec<u64> = vec![0];
-    let mut buf = Vec::new();
-    loop {
-        buf.clear();
-        let bytes_read = reader.read_until(b'\n', &mut buf).expect("failed to scan input file for newlines");
-        if bytes_read == 0 {
-            break;
-        }
-        newline_offsets.push(newline_offsets.last().unwrap() + bytes_read as u64);
-    }
+    puzzle2_checked(input_path).expect("failed to parse number found in a cell")
+}
+
+/// # Puzzle 2 (checked)
+///
+/// Same as puzzle 2, but a sub-column that isn't a valid number is returned as an error mentioning the index of
+/// its character column (counted from 0 on the left) and the offending characters, like `puzzle1_checked`.
+pub fn puzzle2_checked(input_path: &str) -> Result<i64, String> {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    puzzle2_from_source(&input_file, &newline_offsets)
+}
+
+/// # Both puzzles
+///
+/// Solves puzzle 1 and puzzle 2 from a single scan of the newline offsets, which both the left-to-right pass of
+/// puzzle 1 and the right-to-left pass of puzzle 2 share. Returns `(puzzle1, puzzle2)`.
+#[must_use]
+pub fn solve_both(input_path: &str) -> (i64, i64) {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    let puzzle1 = puzzle1_from_source(&input_file, &newline_offsets, ScanOptions::default())
+        .expect("failed to parse number found in a cell");
+    let puzzle2 = puzzle2_from_source(&input_file, &newline_offsets).expect("failed to parse number found in a cell");
+    (puzzle1, puzzle2)
+}
+
+fn puzzle2_from_source(source: &impl ByteSource, newline_offsets: &[u64]) -> Result<i64, String> {
     let mut current_offsets: Vec<_> = newline_offsets
         .windows(2)
         // Iterate in reverse order
         .map(|w| (w[0]..w[1]).rev())
         .collect();
     let offsets_len = current_offsets.len();
-    input_file = reader.into_inner();
     let mut total_sum: i64 = 0;
     let mut keep_processing = true;
     let mut numbers: Vec<i64> = vec![];
@@ -149,57 +320,93 @@ pub fn puzzle2(input_path: &str) -> i64 {
     // Keep processing columns and sub-columns until no new character is left to be parsed
     while keep_processing {
         keep_processing = false;
+        // Once all lines are exhausted, the last pass is blank as well, which finishes the leftmost problem
+        let mut blank_sub_column = true;
         // Read every line in a vertical fashion, one character at a time
         for (i, iter) in &mut current_offsets.iter_mut().enumerate() {
             let is_last_offset = i == offsets_len - 1;
             // Process the next character in a line, but skip newline characters
             for char_offset in iter.by_ref() {
-                let mut buf = [0u8; 1];
-                input_file.read_at(&mut buf, char_offset).expect("failed to read the next byte");
-                let c = buf[0] as char;
+                let c = source.byte_at(char_offset) as char;
                 if c == '\n' {
                     continue;
                 } else if c.is_whitespace() {
                     keep_processing = true;
-                } else if !is_last_offset && (c.is_ascii_digit() || c == '-') {
-                    keep_processing = true;
-                    num_string.push(c);
                 } else if c == '+' {
                     keep_processing = true;
+                    blank_sub_column = false;
                     operation = Some(Operations::Add);
                 } else if  c == '*' {
                     keep_processing = true;
+                    blank_sub_column = false;
                     operation = Some(Operations::Multiply);
+                } else {
+                    // Anything else is kept, so that an invalid cell fails to parse rather than being skipped
+                    keep_processing = true;
+                    blank_sub_column = false;
+                    num_string.push(c);
                 }
                 // Once the end of a sub-column is reached, parse the string into a number if possible
                 if is_last_offset && !num_string.is_empty() {
-                    numbers.push(num_string.parse::<i64>().expect("failed to parse number found in a cell"));
+                    let column = char_offset - newline_offsets[i];
+                    let number = num_string
+                        .parse::<i64>()
+                        .map_err(|e| format!("invalid number {:?} in column {}: {}", num_string, column, e))?;
+                    numbers.push(number);
                     num_string = String::new();
                 }
                 break;
             }
-            // An operation symbol is only available once all sub-columns have been processed,
-            // at which point all the numbers will be added or multiplied together
-            if let Some(ref o) = operation && !numbers.is_empty() {
-                match o {
-                    Operations::Add => {
-                        let res: i64 = numbers.iter().sum();
-                        total_sum += res;
-                    },
-                    Operations::Multiply => {
-                        let res: i64 = numbers.iter().product();
-                        total_sum += res;
-                    }
-                }
-                numbers = vec![];
-                operation = None;
+            // A fully blank sub-column is purely a separator, so all sub-columns of a problem have been processed
+            // once it's reached, at which point all the numbers will be added or multiplied together. This way, the
+            // operation symbol may appear on any line and in any sub-column, and any number of blank sub-columns
+            // may separate the problems
+            if is_last_offset && blank_sub_column {
+                total_sum += finish_problem(&mut operation, &mut numbers)?;
             }
         }
         if !keep_processing {
             break;
         }
     }
-    total_sum
+    Ok(total_sum)
+}
+
+// Adds or multiplies the numbers of a problem once all of them have been read, and resets them for the next problem
+fn finish_problem(operation: &mut Option<Operations>, numbers: &mut Vec<i64>) -> Result<i64, String> {
+    let result = match operation.take() {
+        _ if numbers.is_empty() => 0,
+        Some(Operations::Add) => numbers.iter().sum(),
+        Some(Operations::Multiply) => numbers.iter().product(),
+        None => return Err(format!("no operation symbol found for the numbers {:?}", numbers)),
+    };
+    numbers.clear();
+    Ok(result)
+}
+
+/// # Puzzle 1 (memory-mapped)
+///
+/// Same as puzzle 1, but the input file is memory-mapped, so scanning the columns reads from memory rather than
+/// issuing a system call for every byte. This makes a big difference for extremely wide files.
+#[cfg(feature = "mmap")]
+#[must_use]
+pub fn puzzle1_mmap(input_path: &str) -> i64 {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    // SAFETY: The file must not be modified while it's mapped, which is assumed for a puzzle input
+    let mmap = unsafe { memmap2::Mmap::map(&input_file) }.expect("failed to memory-map input for day 6");
+    puzzle1_from_source(&mmap, &newline_offsets, ScanOptions::default()).expect("failed to parse number found in a cell")
+}
+
+/// # Puzzle 2 (memory-mapped)
+///
+/// Same as puzzle 2, but the input file is memory-mapped like in `puzzle1_mmap`.
+#[cfg(feature = "mmap")]
+#[must_use]
+pub fn puzzle2_mmap(input_path: &str) -> i64 {
+    let (input_file, newline_offsets) = scan_newline_offsets(input_path);
+    // SAFETY: The file must not be modified while it's mapped, which is assumed for a puzzle input
+    let mmap = unsafe { memmap2::Mmap::map(&input_file) }.expect("failed to memory-map input for day 6");
+    puzzle2_from_source(&mmap, &newline_offsets).expect("failed to parse number found in a cell")
 }
 
 #[cfg(test)]
@@ -223,4 +430,132 @@ mod tests {
     fn test_puzzle2() {
        assert_eq!(puzzle2(&utils::string_to_file(TEST_INPUT)), 3263827);
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_matches_read_at() {
+        for input in [TEST_INPUT, "-1 -8\n25 79\n3    \n*  + \n"] {
+            let path = utils::string_to_file(input);
+            assert_eq!(puzzle1_mmap(&path), puzzle1(&path));
+            assert_eq!(puzzle2_mmap(&path), puzzle2(&path));
+        }
+        let path = utils::string_to_file("-12   3 \n  4  -5 \n  *   + \n");
+        assert_eq!(puzzle1_mmap(&path), puzzle1(&path));
+    }
+
+    #[test]
+    fn test_describe_input() {
+        assert_eq!(describe_input(&utils::string_to_file(TEST_INPUT)), (4, 4));
+        assert_eq!(describe_input(&utils::string_to_file(&format!("{}\n\n", TEST_INPUT))), (4, 4));
+        let input = "123 328\n +   45\n 45   *\n  6  98\n";
+        assert_eq!(describe_input(&utils::string_to_file(input)), (4, 2));
+    }
+
+    #[test]
+    fn test_puzzle1_negative_numbers() {
+        let input = "-12   3 \n  4  -5 \n  *   + \n";
+        assert_eq!(puzzle1(&utils::string_to_file(input)), -50);
+    }
+
+    #[test]
+    fn test_puzzle2_negative_numbers() {
+        // The minus sign sits at the top of a sub-column, so -23 is written as -, 2, 3 from top to bottom
+        let input = "-1 -8\n25 79\n3    \n*  + \n";
+        assert_eq!(puzzle2(&utils::string_to_file(input)), -263);
+    }
+
+    #[test]
+    fn test_operation_in_middle_row() {
+        let input = "123 328\n +   45\n 45   *\n  6  98\n";
+        assert_eq!(puzzle1(&utils::string_to_file(input)), 123 + 45 + 6 + 328 * 45 * 98);
+        let input = "12 3\n+  *\n45 6\n";
+        assert_eq!(puzzle2(&utils::string_to_file(input)), 14 + 25 + 36);
+    }
+
+    #[test]
+    fn test_puzzle1_row_oriented() {
+        let input = "123 45 6 *\n328 64 98 +\n51 387 215 *\n64 23 314 +\n";
+        assert_eq!(puzzle1_row_oriented(input), puzzle1(&utils::string_to_file(TEST_INPUT)));
+    }
+
+    #[test]
+    fn test_checked_reports_column() {
+        let path = utils::string_to_file("12  3a \n 4   5 \n *   + \n");
+        let error = puzzle1_checked(&path).unwrap_err();
+        assert!(error.contains("column 1") && error.contains("\"3a\""), "{}", error);
+        let error = puzzle2_checked(&path).unwrap_err();
+        assert!(error.contains("column 5") && error.contains("\"a5\""), "{}", error);
+        assert_eq!(puzzle1_checked(&utils::string_to_file(TEST_INPUT)), Ok(4277556));
+        assert_eq!(puzzle2_checked(&utils::string_to_file(TEST_INPUT)), Ok(3263827));
+    }
+
+    #[test]
+    fn test_blank_columns() {
+        // Two fully blank columns between the problems are just a wider separator
+        let input = "123   328  51   64 \n 45   64  387   23 \n  6   98  215   314\n*     +   *     +  \n";
+        let path = utils::string_to_file(input);
+        assert_eq!(puzzle1(&path), 4277556);
+        assert_eq!(puzzle2(&path), 3263827);
+        // Trailing blank columns are ignored as well
+        let path = utils::string_to_file("12    \n 3    \n+     \n");
+        assert_eq!(puzzle1(&path), 15);
+        assert_eq!(puzzle2(&path), 23 + 1);
+        // The operation symbol of a problem may be under any of its sub-columns
+        let path = utils::string_to_file("12 3\n45 6\n +  *\n");
+        assert_eq!(puzzle2(&path), 14 + 25 + 36);
+        assert!(puzzle2_checked(&utils::string_to_file("12 3\n45 6\n   *\n")).is_err());
+    }
+
+    #[test]
+    fn test_puzzle1_grouped() {
+        let input_path = utils::string_to_file("1,234 2_000\n   10     3\n    +     *\n");
+        assert_eq!(puzzle1_grouped(&input_path), 1244 + 6000);
+        assert!(puzzle1_checked(&input_path).is_err());
+    }
+
+    #[test]
+    fn test_puzzle1_float() {
+        let input_path = utils::string_to_file("1.5e3  2E-2 0.5\n   10 1e+2 1.5\n    +    *   *\n");
+        assert_eq!(puzzle1_float(&input_path), 1510.0 + 2.0 + 0.75);
+        assert_eq!(puzzle1_float(&utils::string_to_file(TEST_INPUT)), puzzle1(&utils::string_to_file(TEST_INPUT)) as f64);
+    }
+
+    #[test]
+    fn test_puzzle1_empty_identity() {
+        // The second column has no numbers, so it's either dropped or counted as the empty product
+        let input_path = utils::string_to_file("3 \n5 \n+ *\n");
+        assert_eq!(puzzle1(&input_path), 8);
+        assert_eq!(puzzle1_empty_identity(&input_path), 9);
+        assert_eq!(puzzle1_empty_identity(&utils::string_to_file("* + *\n")), 2);
+        assert_eq!(puzzle1_empty_identity(&utils::string_to_file(TEST_INPUT)), puzzle1(&utils::string_to_file(TEST_INPUT)));
+    }
+
+    #[test]
+    fn test_transpose_ascii() {
+        let input_path = utils::string_to_file(TEST_INPUT);
+        let out_path = std::env::temp_dir().join(format!("tmp_transposed_{:?}", std::thread::current().id()));
+        assert_eq!(utils::transpose_ascii(&input_path, &out_path, ',').unwrap(), 4);
+        let transposed = std::fs::read_to_string(&out_path).unwrap();
+        assert_eq!(transposed, "123,45,6,*\n328,64,98,+\n51,387,215,*\n64,23,314,+\n");
+        // With whitespace as the delimiter, the transposed file is the row-oriented input of puzzle 1
+        utils::transpose_ascii(&input_path, &out_path, ' ').unwrap();
+        assert_eq!(puzzle1_row_oriented(&std::fs::read_to_string(&out_path).unwrap()), puzzle1(&input_path));
+        // A missing cell would shift the rest of its line into the wrong rows
+        let ragged_path = utils::string_to_file("123 328  51 64 \n 45  64    23 \n  6 98  215 314\n*   +   *   +  \n");
+        let err = utils::transpose_ascii(&ragged_path, &out_path, ',').unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 2 has 3 cells rather than 4");
+        // Lines longer than the scan buffer, the last one without a newline
+        let wide = format!("{}\n{}", " 12".repeat(5000), " 34".repeat(5000));
+        assert_eq!(utils::transpose_ascii(utils::string_to_file(&wide), &out_path, ',').unwrap(), 5000);
+        assert_eq!(std::fs::read_to_string(&out_path).unwrap(), "12,34\n".repeat(5000));
+        let ragged = format!("{}\n{}", " 12".repeat(5000), " 34".repeat(4999));
+        let err = utils::transpose_ascii(utils::string_to_file(&ragged), &out_path, ',').unwrap_err();
+        assert_eq!(err.to_string(), "line 2 has 4999 cells rather than 5000");
+    }
+
+    #[test]
+    fn test_solve_both() {
+        assert_eq!(solve_both(&utils::string_to_file(TEST_INPUT)), (4277556, 3263827));
+    }
+}
@@ -1,6 +1,9 @@
 //! # Day 7: Laboratories ([challenge description](https://adventofcode.com/2025/day/7))
 
-use std::{cell::RefCell, collections::{HashMap, HashSet}, fs::File, io::{BufReader, Read, Seek, SeekFrom}, os::unix::fs::FileExt, rc::Rc};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, fs::File, io::{BufRead, BufReader, Read, Seek, SeekFrom}, os::unix::fs::FileExt, rc::Rc};
+
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
 
 // Due to shared ownership, a Rc<RefCell<T>> needs to be used
 type RayNodeRef = Rc<RefCell<RayNode>>;
@@ -47,15 +50,129 @@ impl RayNode {
         cache.insert(key, total_paths);
         total_paths
     }
+
+    // The largest number of edges from the node to a leaf, memoized by position like count_unique_paths
+    fn max_depth(root_node: &RayNodeRef, cache: &mut HashMap<(usize, usize), usize>) -> usize {
+        let rn = root_node.borrow();
+        let key = (rn.line_index, rn.char_index);
+        if let Some(hit) = cache.get(&key) {
+            return *hit
+        }
+        let children = rn.children.clone();
+        drop(rn);
+        let depth = children
+            .iter()
+            .map(|c| RayNode::max_depth(c, cache) + 1)
+            .max()
+            .unwrap_or(0);
+        cache.insert(key, depth);
+        depth
+    }
+
+    // Counts the distinct nodes reachable from the root node, where shared nodes are only counted once
+    #[cfg(test)]
+    fn count_nodes(root_node: &RayNodeRef) -> usize {
+        let mut visited = HashSet::new();
+        let mut stack = vec![Rc::clone(root_node)];
+        while let Some(node) = stack.pop() {
+            if visited.insert(Rc::as_ptr(&node)) {
+                stack.extend(node.borrow().children.iter().cloned());
+            }
+        }
+        visited.len()
+    }
+
+    // Same as count_unique_paths, but with an arbitrarily large accumulator, since the number of paths can grow
+    // exponentially with the number of rows and even overflow a u64
+    #[cfg(feature = "bigint")]
+    fn count_unique_paths_big(root_node: &RayNodeRef) -> BigUint {
+        let mut cache = HashMap::new();
+        Self::count_unique_paths_big_dfs_memoized(root_node, &mut cache)
+    }
+
+    #[cfg(feature = "bigint")]
+    fn count_unique_paths_big_dfs_memoized(root_node: &RayNodeRef, cache: &mut HashMap<(usize, usize), BigUint>) -> BigUint {
+        let rn = root_node.borrow();
+        let key = (rn.line_index, rn.char_index);
+        if let Some(hit) = cache.get(&key) {
+            return hit.clone()
+        }
+        if rn.children.is_empty() {
+            return BigUint::from(1u8)
+        }
+        let children = rn.children.clone();
+        drop(rn);
+        let mut total_paths = BigUint::ZERO;
+        for c in &children {
+            total_paths += RayNode::count_unique_paths_big_dfs_memoized(c, cache);
+        }
+        cache.insert(key, total_paths.clone());
+        total_paths
+    }
+
+    // Same as count_unique_paths, but only counts paths reaching a leaf within max_depth edges. Since the number of
+    // paths below a node depends on how many edges are left to traverse, the cache key includes the remaining depth
+    fn count_paths_max_depth(root_node: &RayNodeRef, max_depth: usize) -> u64 {
+        let mut cache = HashMap::new();
+        Self::count_paths_max_depth_memoized(root_node, max_depth, &mut cache)
+    }
+
+    fn count_paths_max_depth_memoized(root_node: &RayNodeRef, remaining_depth: usize, cache: &mut HashMap<((usize, usize), usize), u64>) -> u64 {
+        let rn = root_node.borrow();
+        let key = ((rn.line_index, rn.char_index), remaining_depth);
+        if let Some(hit) = cache.get(&key) {
+            return *hit
+        }
+        if rn.children.is_empty() {
+            return 1
+        }
+        if remaining_depth == 0 {
+            return 0
+        }
+        let children = rn.children.clone();
+        drop(rn);
+        let mut total_paths = 0u64;
+        for c in &children {
+            total_paths += RayNode::count_paths_max_depth_memoized(c, remaining_depth - 1, cache);
+        }
+        cache.insert(key, total_paths);
+        total_paths
+    }
+}
+
+// A ray node of the arena graph, which refers to its children by their index in the arena rather than sharing them.
+// The position of a node is only needed while building the arena, so it's kept in a separate map
+#[derive(Debug, Default)]
+struct ArenaNode {
+    children: Vec<usize>,
+}
+
+// Children are always pushed after their parents, so the paths can be counted from the back of the arena without
+// any recursion, and the root node is the first one
+fn count_arena_paths(arena: &[ArenaNode]) -> u64 {
+    let mut paths = vec![0u64; arena.len()];
+    for (i, node) in arena.iter().enumerate().rev() {
+        paths[i] = if node.children.is_empty() {
+            1
+        } else {
+            node.children.iter().map(|&c| paths[c]).sum()
+        };
+    }
+    paths.first().copied().unwrap_or(0)
 }
 
 #[derive(Debug)]
 struct Grid {
     rows: usize,
     columns: usize,
+    // The number of bytes from the start of a row to the start of the next row, which is more than the number of
+    // columns if the rows are separated by blank lines
+    stride: usize,
     file: File,
     // Specifically for Puzzle 1
     rays: HashSet<usize>,
+    // The largest number of rays active after any processed row
+    max_rays: usize,
     // Specifically for Puzzle 2
     root_ray_node: Option<RayNodeRef>,
     ray_map: HashMap<usize, Vec<RayNodeRef>>,
@@ -98,7 +215,9 @@ impl TryFrom<&str> for Grid {
         Ok(Grid { 
             rows,
             columns: line_len,
+            stride: line_len,
             rays: HashSet::new(),
+            max_rays: 0,
             file: reader.into_inner(),
             root_ray_node: None,
             ray_map: HashMap::new(),
@@ -107,14 +226,38 @@ impl TryFrom<&str> for Grid {
 }
 
 impl Grid {
-    pub fn count_ray_splits(mut self) -> u64 {
+    // Adjusts the rows for grids with the given number of blank lines between every two rows
+    fn with_blank_lines(mut self, blank_lines: usize) -> Result<Self, &'static str> {
+        let mut newline = [0u8; 2];
+        // The blank lines are assumed to end like the rows, with either \n or \r\n
+        let newline_len = match self.columns {
+            0 | 1 => 1,
+            c => {
+                self.file.read_exact_at(&mut newline, (c - 2) as u64).map_err(|_| "failed to read line ending")?;
+                if newline == *b"\r\n" { 2 } else { 1 }
+            }
+        };
+        let file_len = self.file.metadata().map_err(|_| "failed to get the length of the file")?.len() as usize;
+        let gap = blank_lines * newline_len;
+        self.stride = self.columns + gap;
+        // The last row isn't necessarily followed by blank lines, or even by a line ending
+        self.rows = (file_len + gap + newline_len) / self.stride;
+        Ok(self)
+    }
+    // The active rays are kept in the grid after the pass, e.g. to count the rays exiting the bottom row
+    pub fn count_ray_splits(&mut self) -> u64 {
+        self.count_ray_splits_step(2)
+    }
+    // Same as count_ray_splits, but splits may occur every step rows rather than every other row. Panics if step is 0
+    pub fn count_ray_splits_step(&mut self, step: usize) -> u64 {
+        assert!(step > 0, "the row step must be at least 1");
         let mut total_splits = 0u64;
         let mut char_buf = [0u8; 1];
-        for r in (0..self.rows).step_by(2) {
+        for r in (0..self.rows).step_by(step) {
             let mut rays_to_add: Vec<usize> = vec![];
             let mut rays_to_remove: Vec<usize> = vec![];
             for c in 0..self.columns {
-                self.file.read_at(&mut char_buf, ((r * self.columns) + c) as u64).expect("failed to read character");
+                self.file.read_at(&mut char_buf, ((r * self.stride) + c) as u64).expect("failed to read character");
                 if r == 0 && char_buf[0] == b'S' {
                     self.rays.insert(c);
                     break;
@@ -134,16 +277,74 @@ impl Grid {
             for ra in rays_to_add {
                 self.rays.insert(ra);
             }
+            self.max_rays = self.max_rays.max(self.rays.len());
         }
         total_splits
     }
-    pub fn build_graph(mut self) -> Rc<RefCell<RayNode>> {
+    // Same as count_ray_splits, but in diagonal mode the two rays emitted by a split keep moving one column
+    // to their side on every processed row, rather than going straight down. Each ray is tracked together with
+    // its direction, so converging rays are only merged if they also move in the same direction
+    pub fn count_ray_splits_diagonal(&mut self, diagonal: bool) -> u64 {
+        let mut total_splits = 0u64;
         let mut char_buf = [0u8; 1];
+        let mut rays: HashSet<(usize, isize)> = HashSet::new();
         for r in (0..self.rows).step_by(2) {
-            let mut rays_to_add: HashMap<usize, Vec<RayNodeRef>> = HashMap::new();
+            if r == 0 {
+                for c in 0..self.columns {
+                    self.file.read_at(&mut char_buf, c as u64).expect("failed to read character");
+                    if char_buf[0] == b'S' {
+                        rays.insert((c, 0));
+                        break;
+                    }
+                }
+                continue;
+            }
+            let mut next_rays: HashSet<(usize, isize)> = HashSet::new();
+            let mut split_columns: HashSet<usize> = HashSet::new();
+            for (c, direction) in rays {
+                // Rays leaving the visible part of the line are gone
+                let Some(c) = c.checked_add_signed(direction).filter(|&c| c < self.columns) else {
+                    continue;
+                };
+                self.file.read_at(&mut char_buf, ((r * self.stride) + c) as u64).expect("failed to read character");
+                match char_buf[0] {
+                    b'^' => {
+                        split_columns.insert(c);
+                        let (left, right) = if diagonal { (-1, 1) } else { (0, 0) };
+                        if c > 0 {
+                            next_rays.insert((c - 1, left));
+                        }
+                        next_rays.insert((c + 1, right));
+                    },
+                    b'\n' | b'\r' => {},
+                    _ => {
+                        next_rays.insert((c, direction));
+                    }
+                }
+            }
+            total_splits += split_columns.len() as u64;
+            rays = next_rays;
+        }
+        total_splits
+    }
+    // Returns the root node, or None if the first row has no S character to emit a ray
+    pub fn build_graph(self) -> Option<RayNodeRef> {
+        self.build_graph_step(2)
+    }
+    // Same as build_graph, but splits may occur every step rows rather than every other row. Panics if step is 0
+    pub fn build_graph_step(self, step: usize) -> Option<RayNodeRef> {
+        self.build_graph_with(step, &split_on_caret)
+    }
+    // Same as build_graph_step, but the rule decides what happens to a ray reaching each character
+    pub fn build_graph_with(mut self, step: usize, rule: &impl Fn(u8) -> SplitAction) -> Option<RayNodeRef> {
+        assert!(step > 0, "the row step must be at least 1");
+        let mut char_buf = [0u8; 1];
+        for r in (0..self.rows).step_by(step) {
+            // The children created in this row, keyed by column, so that rays converging on the same position share a node
+            let mut rays_to_add: HashMap<usize, RayNodeRef> = HashMap::new();
             let mut rays_to_remove: Vec<usize> = vec![];
             for c in 0..self.columns {
-                self.file.read_at(&mut char_buf, ((r * self.columns) + c) as u64).expect("failed to read character");
+                self.file.read_at(&mut char_buf, ((r * self.stride) + c) as u64).expect("failed to read character");
                 if r == 0 && char_buf[0] == b'S' {
                     // Set the root node
                     let root_node = RayNode::new(r, c);
@@ -152,36 +353,208 @@ impl Grid {
                     self.ray_map.insert(c, vec![Rc::clone(&root_node)]);
                     break;
                 }
-                if char_buf[0] == b'^' && let Some(nodes) = self.ray_map.get(&c) {
-                    // Create new children for each ray's node
-                    rays_to_remove.push(c);
-                    let left_child = RayNode::new(r, c-1);
-                    let right_child = RayNode::new(r, c+1);     
+                let Some(nodes) = self.ray_map.get(&c) else {
+                    continue;
+                };
+                let (left, right) = match rule(char_buf[0]) {
+                    SplitAction::None => continue,
+                    SplitAction::Both => (true, true),
+                    SplitAction::Left => (true, false),
+                    SplitAction::Right => (false, true),
+                    SplitAction::Absorb => (false, false),
+                };
+                // Create new children for each ray's node, unless a neighboring split already created them
+                rays_to_remove.push(c);
+                if left && let Some(lc) = c.checked_sub(1) {
+                    let left_child = Rc::clone(rays_to_add.entry(lc).or_insert_with(|| RayNode::new(r, lc)));
                     for node in nodes {
                         RayNode::add_child(node, &left_child);
-                        if c < self.columns {
-                            RayNode::add_child(node, &right_child);
-                        }
                     }
-                    rays_to_add.entry(c - 1).or_default().push(Rc::clone(&left_child));
-                    if c < self.columns {
-                        rays_to_add.entry(c + 1).or_default().push(Rc::clone(&right_child));
+                }
+                if right {
+                    let right_child = Rc::clone(rays_to_add.entry(c + 1).or_insert_with(|| RayNode::new(r, c + 1)));
+                    for node in nodes {
+                        RayNode::add_child(node, &right_child);
                     }
                 }
             }
             for rr in rays_to_remove {
                 self.ray_map.remove(&rr);
             }
-            for (ra, rps) in rays_to_add {
+            for (ra, rp) in rays_to_add {
                 // The new ray positions are mapped to references to new children of existing (root) ray node(s)
-                self.ray_map.entry(ra).or_default().extend(rps);
+                self.ray_map.entry(ra).or_default().push(rp);
             }
         }
-        match self.root_ray_node {
-            Some(rn) => rn,
-            None => panic!("failed to identify the root node")
+        self.root_ray_node
+    }
+    // Same as build_graph, but the nodes are stored in a Vec and deduplicated by their (line_index, char_index) position,
+    // which avoids the reference counting and borrow checks of every Rc<RefCell<RayNode>>. Without an S character,
+    // the arena is empty
+    fn build_graph_arena(self) -> Vec<ArenaNode> {
+        let mut char_buf = [0u8; 1];
+        let mut arena: Vec<ArenaNode> = vec![];
+        let mut positions: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut ray_map: HashMap<usize, Vec<usize>> = HashMap::new();
+        for r in (0..self.rows).step_by(2) {
+            let mut rays_to_add: Vec<usize> = vec![];
+            let mut rays_to_remove: Vec<usize> = vec![];
+            for c in 0..self.columns {
+                self.file.read_at(&mut char_buf, ((r * self.stride) + c) as u64).expect("failed to read character");
+                if r == 0 && char_buf[0] == b'S' {
+                    arena.push(ArenaNode::default());
+                    ray_map.insert(c, vec![0]);
+                    break;
+                }
+                if char_buf[0] != b'^' {
+                    continue;
+                }
+                let Some(nodes) = ray_map.get(&c) else {
+                    continue;
+                };
+                rays_to_remove.push(c);
+                for child_column in [c.checked_sub(1), Some(c + 1)].into_iter().flatten() {
+                    let child = *positions.entry((r, child_column)).or_insert_with(|| {
+                        arena.push(ArenaNode::default());
+                        rays_to_add.push(child_column);
+                        arena.len() - 1
+                    });
+                    for &node in nodes {
+                        arena[node].children.push(child);
+                    }
+                }
+            }
+            for rr in rays_to_remove {
+                ray_map.remove(&rr);
+            }
+            for ra in rays_to_add {
+                ray_map.entry(ra).or_default().push(positions[&(r, ra)]);
+            }
         }
+        arena
     }
+    pub fn render_rays(mut self) -> String {
+        let mut output = String::new();
+        let mut line_buf = vec![0u8; self.columns];
+        for r in 0..self.rows {
+            self.file.read_exact_at(&mut line_buf, (r * self.stride) as u64).expect("failed to read line");
+            // Only draw within the visible part of the line, leaving the newline character(s) untouched
+            let width = line_buf.iter().position(|&b| b == b'\n' || b == b'\r').unwrap_or(self.columns);
+            if r == 0 {
+                if let Some(c) = line_buf[..width].iter().position(|&b| b == b'S') {
+                    self.rays.insert(c);
+                }
+            } else {
+                let mut rays_to_add: Vec<usize> = vec![];
+                let mut rays_to_remove: Vec<usize> = vec![];
+                let mut hops: Vec<(usize, u8)> = vec![];
+                // Visit the rays from left to right, so that the drawing doesn't depend on the hash set's order
+                let mut rays: Vec<usize> = self.rays.iter().copied().collect();
+                rays.sort_unstable();
+                for c in rays {
+                    if line_buf[c] == b'^' {
+                        rays_to_remove.push(c);
+                        if c > 0 {
+                            rays_to_add.push(c - 1);
+                            hops.push((c - 1, b'/'));
+                        }
+                        if c + 1 < width {
+                            rays_to_add.push(c + 1);
+                            hops.push((c + 1, b'\\'));
+                        }
+                    } else if line_buf[c] == b'.' {
+                        line_buf[c] = b'|';
+                    }
+                }
+                // The diagonal hops are drawn last, so they take precedence over rays passing through the same cell.
+                // If two splits hop into the same cell, the one from the left is kept
+                let mut drawn_hops: HashSet<usize> = HashSet::new();
+                for (c, glyph) in hops {
+                    if line_buf[c] != b'^' && drawn_hops.insert(c) {
+                        line_buf[c] = glyph;
+                    }
+                }
+                for rr in rays_to_remove {
+                    self.rays.remove(&rr);
+                }
+                for ra in rays_to_add {
+                    self.rays.insert(ra);
+                }
+            }
+            output.push_str(&String::from_utf8_lossy(&line_buf));
+        }
+        output
+    }
+}
+
+/// What happens to a ray reaching a character of the grid, as decided by the split rule of a `GraphBuilder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitAction {
+    /// The ray keeps going straight down
+    None,
+    /// The ray is split into a ray on either side of the character
+    Both,
+    /// The ray is deflected to the left side of the character
+    Left,
+    /// The ray is deflected to the right side of the character
+    Right,
+    /// The ray ends, so its path ends there as well
+    Absorb,
+}
+
+// The rule of the puzzles: a ^ character splits a ray into two, and everything else lets it through
+fn split_on_caret(cell: u8) -> SplitAction {
+    if cell == b'^' {
+        SplitAction::Both
+    } else {
+        SplitAction::None
+    }
+}
+
+/// Builds the graph of rays of puzzle 2 with a custom split rule, which maps every character a ray reaches to a
+/// `SplitAction`, so variants of the grid (e.g. absorbers or one-sided splitters) don't need a mode of their own.
+/// The rule of the puzzles is `^` splitting a ray in both directions, with every other character letting it through.
+pub struct GraphBuilder<F: Fn(u8) -> SplitAction> {
+    rule: F,
+    step: usize,
+}
+
+impl<F: Fn(u8) -> SplitAction> GraphBuilder<F> {
+    /// Creates a builder applying the rule every other row, like the puzzles.
+    pub fn new(rule: F) -> Self {
+        GraphBuilder { rule, step: 2 }
+    }
+
+    /// Applies the rule every `step` rows instead, like `puzzle2_step`. Panics if the step is 0.
+    #[must_use]
+    pub fn step(mut self, step: usize) -> Self {
+        assert!(step > 0, "the row step must be at least 1");
+        self.step = step;
+        self
+    }
+
+    /// Builds the graph for the input file and counts the unique paths from the S character to the leaves, where
+    /// a leaf is a ray that either exits the bottom of the grid or is absorbed.
+    #[must_use]
+    pub fn count_paths(&self, input_path: &str) -> u64 {
+        let root_node = Grid::try_from(input_path)
+            .expect("failed to construct a grid from the input file")
+            .build_graph_with(self.step, &self.rule);
+        root_node.map_or(0, |rn| RayNode::count_unique_paths(&rn))
+    }
+}
+
+/// A summary of the rays traced through a grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RayStats {
+    /// The number of ^ characters hit by a ray (the answer to puzzle 1)
+    pub splits: u64,
+    /// The number of unique paths from the S character to the bottom (the answer to puzzle 2)
+    pub unique_paths: u64,
+    /// The number of distinct positions at which rays exit the bottom of the grid
+    pub final_beam_width: usize,
+    /// The largest number of splits along a single path
+    pub max_depth: usize,
 }
 
 /// # Puzzle 1
@@ -218,6 +591,227 @@ pub fn puzzle1(input_path: &str) -> u64 {
         .count_ray_splits()
 }
 
+/// # Ray statistics
+///
+/// Computes the answers to both puzzles and a few more statistics from a single graph build, rather than
+/// constructing the grid for each puzzle. Every node of the graph is visited once: a node with children is a ray
+/// that hit a ^ character in the row of its children, so the distinct positions of those characters are the
+/// splits counted by puzzle 1, while the leaves are the rays exiting the bottom of the grid.
+#[must_use]
+pub fn analyze(input_path: &str) -> RayStats {
+    let Some(root_node) = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .build_graph() else {
+        return RayStats::default();
+    };
+    let mut split_positions = HashSet::new();
+    let mut exit_columns = HashSet::new();
+    let mut depths: HashMap<(usize, usize), usize> = HashMap::new();
+    for_each_node(&root_node, |n| match n.children.first() {
+        Some(child) => {
+            split_positions.insert((child.borrow().line_index, n.char_index));
+        },
+        None => {
+            exit_columns.insert(n.char_index);
+        }
+    });
+    RayStats {
+        splits: split_positions.len() as u64,
+        unique_paths: RayNode::count_unique_paths(&root_node),
+        final_beam_width: exit_columns.len(),
+        max_depth: RayNode::max_depth(&root_node, &mut depths),
+    }
+}
+
+/// # Distinct leaf columns
+///
+/// Counts the distinct columns at which the paths of puzzle 2 end. Many paths may end in the same column, so this
+/// is at most the number of unique paths, and it's the same as the `final_beam_width` of `analyze`.
+#[must_use]
+pub fn distinct_leaf_columns(input_path: &str) -> usize {
+    let root_node = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .build_graph();
+    let mut leaf_columns = HashSet::new();
+    if let Some(root_node) = root_node {
+        for_each_node(&root_node, |n| {
+            if n.children.is_empty() {
+                leaf_columns.insert(n.char_index);
+            }
+        });
+    }
+    leaf_columns.len()
+}
+
+// Visits every node of the graph once, even if several rays converge on it
+fn for_each_node(root_node: &RayNodeRef, mut visit: impl FnMut(&RayNode)) {
+    let mut visited = HashSet::new();
+    let mut stack = vec![Rc::clone(root_node)];
+    while let Some(node) = stack.pop() {
+        if !visited.insert(Rc::as_ptr(&node)) {
+            continue;
+        }
+        let n = node.borrow();
+        visit(&n);
+        stack.extend(n.children.iter().cloned());
+    }
+}
+
+/// # Adjacency lists
+///
+/// Builds the graph of puzzle 2 and flattens it into adjacency lists, mapping the `(line_index, char_index)`
+/// position of every ray node to the deduplicated positions of its children, in ascending order. The leaves are
+/// included with no children.
+#[must_use]
+pub fn build_adjacency(input_path: &str) -> HashMap<(usize, usize), Vec<(usize, usize)>> {
+    let root_node = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .build_graph();
+    let mut adjacency = HashMap::new();
+    let mut stack: Vec<RayNodeRef> = root_node.into_iter().collect();
+    while let Some(node) = stack.pop() {
+        let n = node.borrow();
+        let key = (n.line_index, n.char_index);
+        if adjacency.contains_key(&key) {
+            continue;
+        }
+        let mut children: Vec<(usize, usize)> = n.children
+            .iter()
+            .map(|c| {
+                let c = c.borrow();
+                (c.line_index, c.char_index)
+            })
+            .collect();
+        children.sort_unstable();
+        children.dedup();
+        adjacency.insert(key, children);
+        stack.extend(n.children.iter().cloned());
+    }
+    adjacency
+}
+
+/// # Puzzle 1 (sequential)
+///
+/// Same as puzzle 1, but the file is read once from top to bottom through a buffered reader, one line at a time,
+/// rather than reading every character at its offset. Only the columns of the active rays are checked in each line,
+/// so there is neither a system call per character nor any seeking.
+#[must_use]
+pub fn puzzle1_sequential(input_path: &str) -> u64 {
+    let file = File::open(input_path).expect("failed to open input file");
+    let mut reader = BufReader::new(file);
+    let mut line: Vec<u8> = vec![];
+    let mut rays: HashSet<usize> = HashSet::new();
+    let mut total_splits = 0u64;
+    for r in 0.. {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).expect("failed to read line") == 0 {
+            break;
+        }
+        if r == 0 {
+            if let Some(c) = line.iter().position(|&b| b == b'S') {
+                rays.insert(c);
+            }
+            continue;
+        }
+        // Like the other puzzles, splits only occur every other line
+        if r % 2 == 1 {
+            continue;
+        }
+        // All the split rays are removed before the new rays are added, so a ray emitted onto a neighboring ^
+        // character isn't removed in the same line
+        let split_columns: Vec<usize> = rays.iter().copied().filter(|&c| line.get(c) == Some(&b'^')).collect();
+        total_splits += split_columns.len() as u64;
+        for c in &split_columns {
+            rays.remove(c);
+        }
+        for c in split_columns {
+            if let Some(lc) = c.checked_sub(1) {
+                rays.insert(lc);
+            }
+            rays.insert(c + 1);
+        }
+    }
+    total_splits
+}
+
+/// # Puzzle 1 (spaced rows)
+///
+/// Same as puzzle 1, but for a pretty-printed grid with `blank_lines` blank lines between every two rows, which
+/// are skipped rather than treated as rows of the grid.
+#[must_use]
+pub fn puzzle1_spaced(input_path: &str, blank_lines: usize) -> u64 {
+    Grid::try_from(input_path)
+        .and_then(|g| g.with_blank_lines(blank_lines))
+        .expect("failed to construct a grid from the input file")
+        .count_ray_splits()
+}
+
+/// # Split sites
+///
+/// Counts the ^ characters of the grid by scanning the file with a fixed size buffer, without tracing any rays.
+/// Not every ^ character is hit by a ray, so this is an upper bound on the answer to puzzle 1.
+#[must_use]
+pub fn count_split_sites(input_path: &str) -> u64 {
+    let mut reader = BufReader::new(File::open(input_path).expect("failed to open input file"));
+    let mut buf = [0u8; 8192];
+    let mut sites = 0u64;
+    loop {
+        let n = reader.read(&mut buf).expect("failed to read chunk into buffer");
+        if n == 0 {
+            break;
+        }
+        sites += buf[..n].iter().filter(|&&b| b == b'^').count() as u64;
+    }
+    sites
+}
+
+/// # Puzzle 1 (row step)
+///
+/// Same as puzzle 1, but the rows are checked for splits every `step` rows instead of every other row,
+/// e.g. a step of 1 for denser grids where splits may occur in consecutive rows. Panics if the step is 0.
+#[must_use]
+pub fn puzzle1_step(input_path: &str, step: usize) -> u64 {
+    Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .count_ray_splits_step(step)
+}
+
+/// # Puzzle 1 (diagonal rays)
+///
+/// Same as puzzle 1, but with `diagonal` set, the rays emitted by a split move diagonally rather than vertically:
+/// the left ray moves one column to the left and the right ray one column to the right on every other row, where
+/// the next splits are checked. A ^ character hit by several rays in the same row is counted as a single split.
+#[must_use]
+pub fn puzzle1_diagonal(input_path: &str, diagonal: bool) -> u64 {
+    Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .count_ray_splits_diagonal(diagonal)
+}
+
+/// # Final beam width
+///
+/// Returns the number of rays that exit the bottom of the grid after the pass of puzzle 1. Since puzzle 1 merges
+/// converging rays, this is the number of distinct positions the rays end up at, i.e. the leaves of its model.
+#[must_use]
+pub fn final_ray_count(input_path: &str) -> usize {
+    let mut grid = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file");
+    grid.count_ray_splits();
+    grid.rays.len()
+}
+
+/// # Widest beam
+///
+/// Returns the largest number of rays active at the same time while tracing the grid like puzzle 1, where
+/// converging rays count as one.
+#[must_use]
+pub fn max_beam_width(input_path: &str) -> usize {
+    let mut grid = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file");
+    grid.count_ray_splits();
+    grid.max_rays
+}
+
 /// # Puzzle 2
 /// 
 /// ## Summary
@@ -247,7 +841,79 @@ pub fn puzzle2(input_path: &str) -> u64 {
         .build_graph();
     // {:#?} pretty prints if the object implements Debug
     // println!("{:#?}", root_node);
-    RayNode::count_unique_paths(&root_node)
+    root_node.map_or(0, |rn| RayNode::count_unique_paths(&rn))
+}
+
+/// # Puzzle 2 (arena)
+///
+/// Same as puzzle 2, but the graph is stored as a flat arena of nodes referring to their children by index, so
+/// building and traversing it needs neither reference counting nor interior mutability.
+#[must_use]
+pub fn puzzle2_arena(input_path: &str) -> u64 {
+    let arena = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .build_graph_arena();
+    count_arena_paths(&arena)
+}
+
+/// # Puzzle 2 (arbitrarily large)
+///
+/// Same as puzzle 2, but the paths are counted as an arbitrarily large integer, so the exact count is available
+/// even when it exceeds a u64 for very large grids.
+#[cfg(feature = "bigint")]
+#[must_use]
+pub fn puzzle2_big(input_path: &str) -> BigUint {
+    let root_node = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .build_graph();
+    root_node.map_or(BigUint::ZERO, |rn| RayNode::count_unique_paths_big(&rn))
+}
+
+/// # Puzzle 2 (row step)
+///
+/// Same as puzzle 2, with the same row step as `puzzle1_step`, which must be at least 1.
+#[must_use]
+pub fn puzzle2_step(input_path: &str, step: usize) -> u64 {
+    let root_node = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .build_graph_step(step);
+    root_node.map_or(0, |rn| RayNode::count_unique_paths(&rn))
+}
+
+/// # Puzzle 2 (spaced rows)
+///
+/// Same as puzzle 2, with the same blank lines between the rows as `puzzle1_spaced`.
+#[must_use]
+pub fn puzzle2_spaced(input_path: &str, blank_lines: usize) -> u64 {
+    let root_node = Grid::try_from(input_path)
+        .and_then(|g| g.with_blank_lines(blank_lines))
+        .expect("failed to construct a grid from the input file")
+        .build_graph();
+    root_node.map_or(0, |rn| RayNode::count_unique_paths(&rn))
+}
+
+/// # Puzzle 2 (depth-limited)
+///
+/// Counts the unique ray paths like puzzle 2, but only those reaching a leaf after at most `max_depth` splits,
+/// where the depth is the number of edges traversed from the root node. Longer paths are not counted.
+#[must_use]
+pub fn puzzle2_max_depth(input_path: &str, max_depth: usize) -> u64 {
+    let root_node = Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .build_graph();
+    root_node.map_or(0, |rn| RayNode::count_paths_max_depth(&rn, max_depth))
+}
+
+/// # Ray overlay
+///
+/// Returns the original grid with the traced rays drawn in. A ray travelling downward is drawn with a `|` character,
+/// and the two rays emitted by a split hop diagonally to either side of the ^ character, drawn with `/` on the left
+/// and `\\` on the right. Unlike puzzle 1, every line is visited to draw the rays between the lines with splits.
+#[must_use]
+pub fn render_rays(input_path: &str) -> String {
+    Grid::try_from(input_path)
+        .expect("failed to construct a grid from the input file")
+        .render_rays()
 }
 
 #[cfg(test)]
@@ -283,4 +949,235 @@ mod tests {
     fn test_puzzle2() {
        assert_eq!(puzzle2(&utils::string_to_file(TEST_INPUT)), 40);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_puzzle2_max_depth() {
+        let path = utils::string_to_file(TEST_INPUT);
+        assert_eq!(puzzle2_max_depth(&path, 0), 0);
+        assert_eq!(puzzle2_max_depth(&path, 5), 17);
+        assert_eq!(puzzle2_max_depth(&path, 6), 18);
+        assert_eq!(puzzle2_max_depth(&path, 7), 40);
+    }
+
+    #[test]
+    fn test_render_rays() {
+        let rendered = render_rays(&utils::string_to_file(TEST_INPUT));
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), TEST_INPUT.lines().count());
+        assert_eq!(lines[0], ".......S.......");
+        assert_eq!(lines[1], ".......|.......");
+        assert_eq!(lines[2], "....../^\\......");
+        assert_eq!(lines[3], "......|.|......");
+        assert_eq!(lines[4], "...../^\\^\\.....");
+    }
+
+    #[test]
+    fn test_final_ray_count() {
+        assert_eq!(final_ray_count(&utils::string_to_file(TEST_INPUT)), 9);
+    }
+
+    #[test]
+    fn test_step() {
+        let path = utils::string_to_file(TEST_INPUT);
+        assert_eq!(puzzle1_step(&path, 2), 21);
+        assert_eq!(puzzle2_step(&path, 2), 40);
+        let dense_path = utils::string_to_file("..S..\n..^..\n.^.^.\n");
+        assert_eq!(puzzle1_step(&dense_path, 1), 3);
+        assert_eq!(puzzle2_step(&dense_path, 1), 4);
+        assert_eq!(puzzle1_step(&dense_path, 2), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the row step must be at least 1")]
+    fn test_step_zero() {
+        let path = utils::string_to_file(TEST_INPUT);
+        let _ = puzzle1_step(&path, 0);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_puzzle2_big() {
+        assert_eq!(puzzle2_big(&utils::string_to_file(TEST_INPUT)), BigUint::from(40u8));
+        // A full pyramid of splits where every ray hits a ^ on every level, doubling the number of paths per level
+        let levels = 70;
+        let width = 2 * levels + 3;
+        let center = levels + 1;
+        let mut rows = vec![vec![b'.'; width]; 2 * levels + 2];
+        rows[0][center] = b'S';
+        for k in 1..=levels {
+            for j in 0..k {
+                rows[2 * k][center - (k - 1) + 2 * j] = b'^';
+            }
+        }
+        let grid: String = rows.iter().map(|r| String::from_utf8_lossy(r) + "\n").collect();
+        let paths = puzzle2_big(&utils::string_to_file(&grid));
+        assert_eq!(paths, BigUint::from(2u8).pow(levels as u32));
+        assert!(paths > BigUint::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_build_graph_shares_converging_nodes() {
+        let root_node = Grid::try_from(utils::string_to_file(TEST_INPUT).as_str()).unwrap().build_graph().unwrap();
+        assert_eq!(RayNode::count_unique_paths(&root_node), 40);
+        // Both splits in the second row of splits hop into the middle column, which is a single shared node
+        let root_node = Grid::try_from(utils::string_to_file("..S..\n.....\n..^..\n.....\n.^.^.\n.....\n").as_str())
+            .unwrap()
+            .build_graph()
+            .unwrap();
+        assert_eq!(RayNode::count_nodes(&root_node), 6);
+        assert_eq!(RayNode::count_unique_paths(&root_node), 4);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let stats = analyze(&utils::string_to_file(TEST_INPUT));
+        assert_eq!(stats.splits, 21);
+        assert_eq!(stats.unique_paths, 40);
+        assert_eq!(stats.final_beam_width, final_ray_count(&utils::string_to_file(TEST_INPUT)));
+        assert_eq!(stats.max_depth, 7);
+    }
+
+    #[test]
+    fn test_build_adjacency() {
+        let adjacency = build_adjacency(&utils::string_to_file(TEST_INPUT));
+        assert_eq!(adjacency[&(0, 7)], vec![(2, 6), (2, 8)]);
+        assert_eq!(adjacency[&(2, 6)], vec![(4, 5), (4, 7)]);
+        // The leaves are the rays exiting the bottom of the grid, some of which share a column
+        let exit_columns: HashSet<usize> = adjacency.iter().filter(|(_, c)| c.is_empty()).map(|(&(_, c), _)| c).collect();
+        assert_eq!(exit_columns.len(), 9);
+    }
+
+    #[test]
+    fn test_puzzle1_diagonal() {
+        assert_eq!(puzzle1_diagonal(&utils::string_to_file(TEST_INPUT), false), 21);
+        // The first split emits rays drifting to columns 1 and 5, which both split again. The inner rays of those
+        // splits then converge on the last ^ character, while the outer rays leave the grid
+        let path = utils::string_to_file("...S...\n.......\n...^...\n.......\n.^...^.\n.......\n...^...\n.......\n");
+        assert_eq!(puzzle1_diagonal(&path, true), 4);
+        assert_eq!(puzzle1_diagonal(&path, false), 1);
+    }
+
+    #[test]
+    fn test_converging_rays() {
+        // The two ^ characters one column apart both emit a ray into the middle column. Puzzle 1 merges them into
+        // a single ray, while puzzle 2 keeps both paths through it
+        let path = utils::string_to_file("...S...\n.......\n...^...\n.......\n..^.^..\n.......\n");
+        assert_eq!(puzzle1(&path), 3);
+        assert_eq!(final_ray_count(&path), 3);
+        assert_eq!(puzzle2(&path), 4);
+        // A ^ below the merge point is only counted once as a split, but doubles both paths through it
+        let path = utils::string_to_file("...S...\n.......\n...^...\n.......\n..^.^..\n.......\n...^...\n.......\n");
+        assert_eq!(puzzle1(&path), 4);
+        assert_eq!(puzzle2(&path), 6);
+        assert_eq!(analyze(&path).splits, 4);
+    }
+
+    #[test]
+    fn test_graph_builder() {
+        let path = utils::string_to_file(TEST_INPUT);
+        assert_eq!(GraphBuilder::new(split_on_caret).count_paths(&path), 40);
+        // The first ^ character absorbs the only ray
+        assert_eq!(GraphBuilder::new(|c| if c == b'^' { SplitAction::Absorb } else { SplitAction::None }).count_paths(&path), 1);
+        // The ray on the left is split again, the one on the right is absorbed, and the > deflects no ray
+        let rule = |c| match c {
+            b'^' => SplitAction::Both,
+            b'#' => SplitAction::Absorb,
+            b'>' => SplitAction::Right,
+            _ => SplitAction::None,
+        };
+        let path = utils::string_to_file("..S..\n.....\n..^..\n.....\n.^.#>\n.....\n");
+        assert_eq!(GraphBuilder::new(rule).count_paths(&path), 3);
+        let path = utils::string_to_file("..S..\n..^..\n.>.#.\n");
+        assert_eq!(GraphBuilder::new(rule).step(1).count_paths(&path), 2);
+    }
+
+    #[test]
+    fn test_puzzle2_arena() {
+        assert_eq!(puzzle2_arena(&utils::string_to_file(TEST_INPUT)), 40);
+        let arena = Grid::try_from(utils::string_to_file(TEST_INPUT).as_str()).unwrap().build_graph_arena();
+        assert_eq!(arena[0].children.len(), 2);
+        // Random grids with a ^ character in about a third of the cells of every other row
+        let mut state = 0x5eed_u64;
+        for _ in 0..20 {
+            let mut grid = String::from("....S....\n");
+            for r in 1..12 {
+                let row: String = (0..9)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        if r % 2 == 0 && state.is_multiple_of(3) { '^' } else { '.' }
+                    })
+                    .collect();
+                grid.push_str(&row);
+                grid.push('\n');
+            }
+            let path = utils::string_to_file(&grid);
+            assert_eq!(puzzle2_arena(&path), puzzle2(&path), "{}", grid);
+        }
+    }
+
+    #[test]
+    fn test_max_beam_width() {
+        assert_eq!(max_beam_width(&utils::string_to_file(TEST_INPUT)), 9);
+        // The last split emits two rays into columns that are already active, so the beam narrows from 4 to 3 rays
+        let path = utils::string_to_file("...S...\n.......\n...^...\n.......\n..^....\n.......\n.^.....\n.......\n...^...\n.......\n");
+        assert_eq!(max_beam_width(&path), 4);
+        assert_eq!(final_ray_count(&path), 3);
+    }
+
+    #[test]
+    fn test_no_source() {
+        let path = utils::string_to_file(".......\n.......\n...^...\n.......\n");
+        assert_eq!(puzzle1(&path), 0);
+        assert_eq!(puzzle2(&path), 0);
+        assert_eq!(puzzle2_arena(&path), 0);
+        assert_eq!(analyze(&path), RayStats::default());
+        assert!(build_adjacency(&path).is_empty());
+    }
+
+    #[test]
+    fn test_puzzle1_sequential() {
+        assert_eq!(puzzle1_sequential(&utils::string_to_file(TEST_INPUT)), 21);
+        for input in ["...S...\n.......\n...^...\n.......\n..^.^..\n.......\n...^...\n.......\n", ".......\n...^...\n", "..S..\n.....\n..^..\n.....\n.^^^.\n.....\n"] {
+            let path = utils::string_to_file(input);
+            assert_eq!(puzzle1_sequential(&path), puzzle1(&path));
+        }
+    }
+
+    #[test]
+    fn test_distinct_leaf_columns() {
+        let path = utils::string_to_file(TEST_INPUT);
+        assert_eq!(distinct_leaf_columns(&path), 9);
+        assert_eq!(distinct_leaf_columns(&path), analyze(&path).final_beam_width);
+        // Two paths end in the middle column
+        let path = utils::string_to_file("...S...\n.......\n...^...\n.......\n..^.^..\n.......\n");
+        assert_eq!(puzzle2(&path), 4);
+        assert_eq!(distinct_leaf_columns(&path), 3);
+    }
+
+    #[test]
+    fn test_spaced_rows() {
+        let path = utils::string_to_file(&TEST_INPUT.replace('\n', "\n\n"));
+        assert_eq!(puzzle1_spaced(&path, 1), 21);
+        assert_eq!(puzzle2_spaced(&path, 1), 40);
+        // Without a line ending after the last row, which has the only splitter
+        let path = utils::string_to_file("..S..\n\n.....\n\n..^..");
+        assert_eq!(puzzle1_spaced(&path, 1), 1);
+        assert_eq!(puzzle2_spaced(&path, 1), 2);
+        // Same with CRLF line endings
+        let path = utils::string_to_file("..S..\r\n\r\n\r\n.....\r\n\r\n\r\n..^..");
+        assert_eq!(puzzle1_spaced(&path, 2), 1);
+        assert_eq!(puzzle2_spaced(&path, 2), 2);
+        let path = utils::string_to_file(TEST_INPUT);
+        assert_eq!(puzzle1_spaced(&path, 0), 21);
+    }
+
+    #[test]
+    fn test_count_split_sites() {
+        let path = utils::string_to_file(TEST_INPUT);
+        // One of the ^ characters is never reached by a ray
+        assert_eq!(count_split_sites(&path), 22);
+        assert_eq!(puzzle1(&path), 21);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     fs::File,
-    io::{BufReader, prelude::*},
+    io::{BufReader, BufWriter, prelude::*},
+    os::unix::fs::FileExt,
     path::{Path},
 };
 
@@ -24,3 +25,83 @@ pub fn string_to_file(content: &str) -> String {
     std::fs::write(&path, content).unwrap();
     path.into_os_string().into_string().expect("failed to get temp file path")
 }
+
+// Converts a column-oriented ASCII grid, where every line holds one cell of each column separated by whitespace, into
+// a row-oriented file with the cells of each column on a line of their own, joined by the delimiter. Only the read
+// position of each input line is kept in memory, and every output row is gathered by reading the next cell of each
+// line at its position, so the memory used doesn't depend on the length of the lines. Returns the number of rows written.
+// A line with a missing cell would shift the following cells into the wrong rows, so if the lines don't all hold the same
+// number of cells, an InvalidData error is returned before anything is written. Blank lines are skipped.
+pub fn transpose_ascii(input_path: impl AsRef<Path>, out_path: impl AsRef<Path>, delimiter: char) -> std::io::Result<usize> {
+    let mut input = File::open(input_path)?;
+    // The [start, end) offsets of every line, which are moved forward as the cells are read
+    let mut lines: Vec<(u64, u64)> = vec![];
+    // The offsets and cell counts are found while scanning with a fixed size buffer, so that a long line is never
+    // held in memory as a whole
+    let mut cell_count = None;
+    let mut line_i = 0;
+    let mut line_start = 0u64;
+    let mut line_cells = 0;
+    let mut in_cell = false;
+    let mut offset = 0u64;
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = input.read(&mut buf)?;
+        // A last line without a newline is ended by the end of the file
+        let chunk: &[u8] = if n == 0 { b"\n" } else { &buf[..n] };
+        for &b in chunk {
+            if !b.is_ascii_whitespace() {
+                line_cells += usize::from(!in_cell);
+                in_cell = true;
+            } else {
+                in_cell = false;
+            }
+            if b == b'\n' {
+                if line_cells > 0 {
+                    if *cell_count.get_or_insert(line_cells) != line_cells {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("line {} has {} cells rather than {}", line_i + 1, line_cells, cell_count.unwrap_or(0)),
+                        ));
+                    }
+                    lines.push((line_start, offset));
+                }
+                line_i += 1;
+                line_start = offset + 1;
+                line_cells = 0;
+            }
+            offset += 1;
+        }
+        if n == 0 {
+            break;
+        }
+    }
+    let mut out = BufWriter::new(File::create(out_path)?);
+    let mut byte = [0u8; 1];
+    let mut rows = 0;
+    loop {
+        let mut cells: Vec<String> = vec![];
+        for (start, end) in lines.iter_mut() {
+            let mut cell = String::new();
+            while *start < *end {
+                input.read_exact_at(&mut byte, *start)?;
+                *start += 1;
+                if !byte[0].is_ascii_whitespace() {
+                    cell.push(byte[0] as char);
+                } else if !cell.is_empty() {
+                    break;
+                }
+            }
+            if !cell.is_empty() {
+                cells.push(cell);
+            }
+        }
+        if cells.is_empty() {
+            break;
+        }
+        writeln!(out, "{}", cells.join(&delimiter.to_string()))?;
+        rows += 1;
+    }
+    out.flush()?;
+    Ok(rows)
+}